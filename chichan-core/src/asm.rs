@@ -0,0 +1,385 @@
+// Assembler, the inverse of the disassembler
+use crate::disasm::START_ADDRESS;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// An assembly error and the source line it's on
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsmError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AsmError {}
+
+/// A parsed instruction argument
+#[derive(Debug, Clone, PartialEq)]
+enum Arg<'a> {
+    Register(u8),
+    Range(u8, u8),
+    /// `I`, `[I]`, `DT`, `ST`, `K`, `F`, `HF`, `B` or `R`
+    Keyword(&'static str),
+    /// `long <expr>`
+    Long(&'a str),
+    /// Number, label or constant, resolved in the second pass
+    Expr(&'a str),
+}
+
+const KEYWORDS: [&str; 9] = ["I", "[I]", "DT", "ST", "K", "F", "HF", "B", "R"];
+
+/// One instruction or data directive
+struct Statement<'a> {
+    line: usize,
+    mnemonic: String,
+    args: Vec<&'a str>,
+}
+
+/// Assemble source text into a rom loaded at 0x200.
+///
+/// One statement per line, `;` starts a comment:
+///
+/// ```text
+/// SPEED = 3               ; constant
+/// start:                  ; label
+///     LD V0, SPEED
+///     LD I, sprite
+///     DRW V0, V1, 5
+///     JMP start
+/// sprite:
+///     db 0xF0, 0x90, 0x90, 0x90, 0xF0
+///     dw 0x1234           ; big endian
+/// ```
+///
+/// Mnemonics are the ones `chichan disasm` prints, case doesn't matter.
+/// Numbers are decimal, `0x` hex or `0b` binary, and can be summed with labels and constants (`sprite + 5`)
+pub fn assemble(source: &str) -> Result<Vec<u8>, AsmError> {
+    let mut labels: HashMap<&str, usize> = HashMap::new();
+    let mut constants: HashMap<&str, &str> = HashMap::new();
+    let mut statements = Vec::new();
+    let mut address = START_ADDRESS as usize;
+
+    // first pass: addresses of labels and statements
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| AsmError {
+            line: number,
+            message,
+        };
+        let mut line = line.split(';').next().unwrap_or("").trim();
+
+        if let Some(colon) = line.find(':') {
+            let label = line[..colon].trim();
+            if !is_identifier(label) {
+                return Err(error(format!("'{}' is not a valid label", label)));
+            }
+            if is_reserved(label) {
+                return Err(error(format!("'{}' is a register or keyword, it can't be a label", label)));
+            }
+            if labels.insert(label, address).is_some() || constants.contains_key(label) {
+                return Err(error(format!("'{}' is defined twice", label)));
+            }
+            line = line[colon + 1..].trim();
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(equals) = line.find('=') {
+            let name = line[..equals].trim();
+            if is_identifier(name) {
+                if is_reserved(name) {
+                    return Err(error(format!("'{}' is a register or keyword, it can't be a constant", name)));
+                }
+                if constants.insert(name, line[equals + 1..].trim()).is_some()
+                    || labels.contains_key(name)
+                {
+                    return Err(error(format!("'{}' is defined twice", name)));
+                }
+                continue;
+            }
+        }
+
+        let (mnemonic, rest) = match line.find(char::is_whitespace) {
+            Some(space) => (&line[..space], line[space..].trim()),
+            None => (line, ""),
+        };
+        let mnemonic = mnemonic.to_uppercase();
+        let args: Vec<&str> = if rest.is_empty() {
+            Vec::new()
+        } else {
+            rest.split(',').map(str::trim).collect()
+        };
+
+        let size = match mnemonic.as_str() {
+            "DB" => args.len(),
+            "DW" => args.len() * 2,
+            "LD" if args.get(1).is_some_and(|arg| is_long(arg)) => 4,
+            _ => 2,
+        };
+        if (mnemonic == "DB" || mnemonic == "DW") && args.is_empty() {
+            return Err(error(format!("{} needs at least one value", mnemonic.to_lowercase())));
+        }
+
+        statements.push(Statement {
+            line: number,
+            mnemonic,
+            args,
+        });
+        address += size;
+    }
+
+    if address > 0x10000 {
+        return Err(AsmError {
+            line: statements.last().map_or(1, |statement| statement.line),
+            message: format!("rom ends at {:#X}, past the end of memory", address),
+        });
+    }
+
+    // second pass: encode with every label known
+    let symbols = Symbols { labels, constants };
+    let mut rom = Vec::with_capacity(address - START_ADDRESS as usize);
+    for statement in statements.iter() {
+        let bytes = encode(statement, &symbols).map_err(|message| AsmError {
+            line: statement.line,
+            message,
+        })?;
+        rom.extend_from_slice(&bytes);
+    }
+
+    Ok(rom)
+}
+
+struct Symbols<'a> {
+    labels: HashMap<&'a str, usize>,
+    constants: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Symbols<'a> {
+    /// Evaluate a sum of numbers, labels and constants
+    fn eval(&self, expr: &str, depth: usize) -> Result<i64, String> {
+        if depth > 32 {
+            return Err(format!("constant '{}' refers to itself", expr));
+        }
+
+        let expr = expr.trim();
+        if expr.is_empty() {
+            return Err(String::from("missing value"));
+        }
+
+        let mut total = 0;
+        let mut sign = 1;
+        let mut term_start = 0;
+        let bytes = expr.as_bytes();
+        for i in 0..=bytes.len() {
+            let at_operator = i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') && i > term_start;
+            if i == bytes.len() || at_operator {
+                let term = expr[term_start..i].trim();
+                let (term_sign, term) = match term.strip_prefix('-') {
+                    Some(negated) => (-1, negated.trim()),
+                    None => (1, term),
+                };
+                total += sign * term_sign * self.term(term, depth)?;
+
+                if i < bytes.len() {
+                    sign = if bytes[i] == b'+' { 1 } else { -1 };
+                    term_start = i + 1;
+                }
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn term(&self, term: &str, depth: usize) -> Result<i64, String> {
+        if let Some(value) = parse_number(term) {
+            return Ok(value);
+        }
+        if let Some(address) = self.labels.get(term) {
+            return Ok(*address as i64);
+        }
+        if let Some(expr) = self.constants.get(term) {
+            return self.eval(expr, depth + 1);
+        }
+
+        if is_identifier(term) {
+            Err(format!("unknown label or constant '{}'", term))
+        } else {
+            Err(format!("'{}' is not a value", term))
+        }
+    }
+
+    fn value(&self, expr: &str, max: i64, what: &str) -> Result<u16, String> {
+        let value = self.eval(expr, 0)?;
+        if value < 0 || value > max {
+            return Err(format!("{} {} is out of range (0-{:#X})", what, value, max));
+        }
+        Ok(value as u16)
+    }
+}
+
+fn parse_number(s: &str) -> Option<i64> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = s.strip_prefix("0b").or_else(|| s.strip_prefix("0B")) {
+        i64::from_str_radix(binary, 2).ok()
+    } else if s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names `parse_arg` reads as registers or keywords, a symbol named so could never be used
+fn is_reserved(name: &str) -> bool {
+    register(name).is_some() || KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(name))
+}
+
+fn is_long(arg: &str) -> bool {
+    arg.len() > 5 && arg[..5].eq_ignore_ascii_case("long ")
+}
+
+fn register(arg: &str) -> Option<u8> {
+    let digit = arg.strip_prefix('V').or_else(|| arg.strip_prefix('v'))?;
+    if digit.len() != 1 {
+        return None;
+    }
+    u8::from_str_radix(digit, 16).ok()
+}
+
+fn parse_arg(arg: &str) -> Arg<'_> {
+    if let Some(x) = register(arg) {
+        return Arg::Register(x);
+    }
+
+    if let Some(keyword) = KEYWORDS.iter().find(|keyword| keyword.eq_ignore_ascii_case(arg)) {
+        return Arg::Keyword(keyword);
+    }
+
+    if is_long(arg) {
+        return Arg::Long(arg[5..].trim());
+    }
+
+    let mut parts = arg.splitn(2, '-');
+    if let (Some(x), Some(y)) = (parts.next(), parts.next()) {
+        if let (Some(x), Some(y)) = (register(x.trim()), register(y.trim())) {
+            return Arg::Range(x, y);
+        }
+    }
+
+    Arg::Expr(arg)
+}
+
+fn encode(statement: &Statement, symbols: &Symbols) -> Result<Vec<u8>, String> {
+    use Arg::*;
+
+    let mnemonic = statement.mnemonic.as_str();
+    if mnemonic == "DB" || mnemonic == "DW" {
+        let mut bytes = Vec::new();
+        for arg in statement.args.iter() {
+            if mnemonic == "DB" {
+                bytes.push(symbols.value(arg, 0xFF, "byte")? as u8);
+            } else {
+                bytes.extend_from_slice(&symbols.value(arg, 0xFFFF, "word")?.to_be_bytes());
+            }
+        }
+        return Ok(bytes);
+    }
+
+    let args: Vec<Arg> = statement.args.iter().map(|arg| parse_arg(arg)).collect();
+    let addr = |expr: &str| symbols.value(expr, 0xFFF, "address");
+    let byte = |expr: &str| symbols.value(expr, 0xFF, "byte");
+    let nibble = |expr: &str| symbols.value(expr, 0xF, "nibble");
+    let xy = |op: u16, x: u8, y: u8| op | (x as u16) << 8 | (y as u16) << 4;
+    let xkk = |op: u16, x: u8, kk: u16| op | (x as u16) << 8 | kk;
+
+    let opcode: u16 = match (mnemonic, args.as_slice()) {
+        ("CLS", []) => 0x00E0,
+        ("RET", []) => 0x00EE,
+        ("SCD", [Expr(n)]) => 0x00C0 | nibble(n)?,
+        ("SCR", []) => 0x00FB,
+        ("SCL", []) => 0x00FC,
+        ("EXIT", []) => 0x00FD,
+        ("LOW", []) => 0x00FE,
+        ("HIGH", []) => 0x00FF,
+        ("JMP", [Expr(a)]) | ("JP", [Expr(a)]) => 0x1000 | addr(a)?,
+        ("JMP", [Register(0), Expr(a)]) | ("JP", [Register(0), Expr(a)]) => 0xB000 | addr(a)?,
+        ("CALL", [Expr(a)]) => 0x2000 | addr(a)?,
+        ("SE", [Register(x), Expr(kk)]) => xkk(0x3000, *x, byte(kk)?),
+        ("SNE", [Register(x), Expr(kk)]) => xkk(0x4000, *x, byte(kk)?),
+        ("SE", [Register(x), Register(y)]) => xy(0x5000, *x, *y),
+        ("SAVE", [Range(x, y)]) => xy(0x5002, *x, *y),
+        ("LOAD", [Range(x, y)]) => xy(0x5003, *x, *y),
+        ("LD", [Register(x), Expr(kk)]) => xkk(0x6000, *x, byte(kk)?),
+        ("ADD", [Register(x), Expr(kk)]) => xkk(0x7000, *x, byte(kk)?),
+        ("LD", [Register(x), Register(y)]) => xy(0x8000, *x, *y),
+        ("OR", [Register(x), Register(y)]) => xy(0x8001, *x, *y),
+        ("AND", [Register(x), Register(y)]) => xy(0x8002, *x, *y),
+        ("XOR", [Register(x), Register(y)]) => xy(0x8003, *x, *y),
+        ("ADD", [Register(x), Register(y)]) => xy(0x8004, *x, *y),
+        ("SUB", [Register(x), Register(y)]) => xy(0x8005, *x, *y),
+        ("SHR", [Register(x)]) => xy(0x8006, *x, 0),
+        ("SHR", [Register(x), Register(y)]) => xy(0x8006, *x, *y),
+        ("SUBN", [Register(x), Register(y)]) => xy(0x8007, *x, *y),
+        ("SHL", [Register(x)]) => xy(0x800E, *x, 0),
+        ("SHL", [Register(x), Register(y)]) => xy(0x800E, *x, *y),
+        ("SNE", [Register(x), Register(y)]) => xy(0x9000, *x, *y),
+        ("LD", [Keyword("I"), Expr(a)]) => 0xA000 | addr(a)?,
+        ("RND", [Register(x), Expr(kk)]) => xkk(0xC000, *x, byte(kk)?),
+        ("DRW", [Register(x), Register(y), Expr(n)]) => xy(0xD000, *x, *y) | nibble(n)?,
+        ("SKP", [Register(x)]) => xkk(0xE09E, *x, 0),
+        ("SKNP", [Register(x)]) => xkk(0xE0A1, *x, 0),
+        ("LD", [Keyword("I"), Long(a)]) => {
+            let long = symbols.value(a, 0xFFFF, "address")?;
+            return Ok(vec![0xF0, 0x00, (long >> 8) as u8, long as u8]);
+        }
+        ("PLANE", [Expr(n)]) => {
+            let n = symbols.value(n, 0x3, "plane mask")?;
+            0xF001 | n << 8
+        }
+        ("AUDIO", []) => 0xF002,
+        ("LD", [Register(x), Keyword("DT")]) => xkk(0xF007, *x, 0),
+        ("LD", [Register(x), Keyword("K")]) => xkk(0xF00A, *x, 0),
+        ("LD", [Keyword("DT"), Register(x)]) => xkk(0xF015, *x, 0),
+        ("LD", [Keyword("ST"), Register(x)]) => xkk(0xF018, *x, 0),
+        ("ADD", [Keyword("I"), Register(x)]) => xkk(0xF01E, *x, 0),
+        ("LD", [Keyword("F"), Register(x)]) => xkk(0xF029, *x, 0),
+        ("LD", [Keyword("HF"), Register(x)]) => xkk(0xF030, *x, 0),
+        ("LD", [Keyword("B"), Register(x)]) => xkk(0xF033, *x, 0),
+        ("PITCH", [Register(x)]) => xkk(0xF03A, *x, 0),
+        ("LD", [Keyword("[I]"), Register(x)]) => xkk(0xF055, *x, 0),
+        ("LD", [Register(x), Keyword("[I]")]) => xkk(0xF065, *x, 0),
+        ("LD", [Keyword("R"), Register(x)]) => xkk(0xF075, *x, 0),
+        ("LD", [Register(x), Keyword("R")]) => xkk(0xF085, *x, 0),
+        _ => {
+            let known = [
+                "CLS", "RET", "SCD", "SCR", "SCL", "EXIT", "LOW", "HIGH", "JMP", "JP", "CALL", "SE", "SNE", "SAVE",
+                "LOAD", "LD", "ADD", "OR", "AND", "XOR", "SUB", "SHR", "SUBN", "SHL", "RND", "DRW", "SKP", "SKNP",
+                "PLANE", "AUDIO", "PITCH",
+            ];
+            return Err(if known.contains(&mnemonic) {
+                format!("invalid operands for {}: '{}'", mnemonic, statement.args.join(", "))
+            } else {
+                format!("unknown instruction '{}'", mnemonic)
+            });
+        }
+    };
+
+    Ok(opcode.to_be_bytes().to_vec())
+}
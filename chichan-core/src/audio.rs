@@ -0,0 +1,148 @@
+// Buzzer sound generation, independent of any audio device
+use crate::chip8::{Chip8, TIMER_HZ};
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+pub const DEFAULT_FREQUENCY: f32 = 440.0;
+pub const DEFAULT_VOLUME: f32 = 0.25;
+/// XO-CHIP pitch register value playing the pattern at 4000 bits per second
+pub const DEFAULT_PITCH: u8 = 64;
+
+/// Bits per second an XO-CHIP audio pattern is played at for a pitch register value
+pub fn pattern_rate(pitch: u8) -> f32 {
+    4000.0 * 2f32.powf((pitch as f32 - 64.0) / 48.0)
+}
+
+/// Anything that accepts mono f32 samples in the -1.0..=1.0 range
+pub trait AudioSink {
+    fn write_samples(&mut self, samples: &[f32]);
+}
+
+/// Square wave generator played while the sound timer is running,
+/// or the XO-CHIP audio pattern once one is set
+#[derive(Debug, Clone)]
+pub struct Buzzer {
+    pub frequency: f32,
+    pub volume: f32,
+    /// 128 1-bit samples looped instead of the square wave
+    pub pattern: Option<[u8; 16]>,
+    /// Pattern playback rate, see `pattern_rate`
+    pub pitch: u8,
+    sample_rate: u32,
+    // position in the current wave period or pattern, 0.0..1.0
+    phase: f32,
+}
+
+impl Buzzer {
+    pub fn new(sample_rate: u32) -> Self {
+        Buzzer {
+            frequency: DEFAULT_FREQUENCY,
+            volume: DEFAULT_VOLUME,
+            pattern: None,
+            pitch: DEFAULT_PITCH,
+            sample_rate,
+            phase: 0.0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Produce the next sample, silence when not beeping
+    pub fn next_sample(&mut self, beeping: bool) -> f32 {
+        let high = match &self.pattern {
+            Some(pattern) => {
+                let bit = (self.phase * 128.0) as usize % 128;
+                pattern[bit / 8] & (0x80 >> (bit % 8)) != 0
+            }
+            None => self.phase < 0.5,
+        };
+
+        let sample = if !beeping {
+            0.0
+        } else if high {
+            self.volume
+        } else {
+            -self.volume
+        };
+
+        // one period is the whole 128-bit pattern
+        let frequency = match self.pattern {
+            Some(_) => pattern_rate(self.pitch) / 128.0,
+            None => self.frequency,
+        };
+
+        // keep the wave running through silence so a new beep doesn't click
+        self.phase = (self.phase + frequency / self.sample_rate as f32).fract();
+
+        sample
+    }
+
+    /// Write `count` samples to `sink`
+    pub fn render(&mut self, beeping: bool, count: usize, sink: &mut dyn AudioSink) {
+        let samples: Vec<f32> = (0..count).map(|_| self.next_sample(beeping)).collect();
+        sink.write_samples(&samples);
+    }
+
+    /// Write one 60 Hz frame worth of samples for the chip's current sound timer and audio pattern
+    pub fn render_frame(&mut self, chip: &Chip8, sink: &mut dyn AudioSink) {
+        self.pattern = chip.audio_pattern;
+        self.pitch = chip.pitch;
+
+        let count = (self.sample_rate / TIMER_HZ) as usize;
+        self.render(chip.is_beeping(), count, sink);
+    }
+}
+
+/// In-memory sink that can be written out as a WAV file
+#[derive(Debug, Clone, Default)]
+pub struct WavBuffer {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl AudioSink for WavBuffer {
+    fn write_samples(&mut self, samples: &[f32]) {
+        self.samples.extend_from_slice(samples);
+    }
+}
+
+impl WavBuffer {
+    pub fn new(sample_rate: u32) -> Self {
+        WavBuffer {
+            sample_rate,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Encode as a 16-bit mono PCM WAV file
+    pub fn to_wav(&self) -> Vec<u8> {
+        let data_len = (self.samples.len() * 2) as u32;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+
+        // RIFF header
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+
+        // format chunk: PCM, 1 channel, 16 bits
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&self.sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(self.sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+
+        // samples
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in self.samples.iter() {
+            let pcm = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            wav.extend_from_slice(&pcm.to_le_bytes());
+        }
+
+        wav
+    }
+}
@@ -0,0 +1,1421 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::str::FromStr;
+
+use crate::framebuffer::{self, Framebuffer};
+use crate::quirks::{EdgeMode, IndexIncrement, Quirks};
+use crate::random::{DefaultRandom, Random, XorShift};
+
+const MEM_SIZE: usize = 4096;
+const XO_MEM_SIZE: usize = 0x10000;
+const START_ADDRESS: usize = 0x200;
+const FONT_SET_START_ADDRESS: usize = 0x50;
+const LARGE_FONT_SET_START_ADDRESS: usize = 0xA0;
+
+/// Rate the delay and sound timers count down at, also the frame rate of `run_frame`
+pub const TIMER_HZ: u32 = 60;
+/// Default number of instructions executed per second
+pub const DEFAULT_SPEED: u32 = 700;
+
+// Sized for XO-CHIP whatever the variant, the others only address the first `MEM_SIZE` bytes.
+// A fixed array keeps the machine free of allocations for `no_std`, at the cost of 60 KiB
+// a CHIP-8 or SUPER-CHIP machine never touches
+type Memory = [u8; XO_MEM_SIZE];
+type OpResult = Result<StepEvent, Chip8Error>;
+type Instruction<R> = fn(&mut Chip8<R>) -> OpResult;
+
+/// What happened during a single `cycle`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepEvent {
+    /// The instruction ran normally
+    Executed,
+    /// `Fx0A` is blocking until a key is pressed
+    WaitingForKey,
+    /// A sprite was drawn with `Dxyn`
+    Drew,
+    /// The rom asked to quit with `00FD`
+    Exited,
+    /// An unknown opcode was skipped under the `Ignore` or `Log` policy
+    UnknownOpcode,
+}
+
+/// What to do when the rom hits an opcode with no instruction behind it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnknownOpcodePolicy {
+    /// Skip it silently
+    Ignore,
+    /// Skip it and print it to stderr
+    Log,
+    /// Stop with `Chip8Error::InvalidOpcode`
+    Halt,
+}
+
+#[cfg(feature = "std")]
+impl FromStr for UnknownOpcodePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(UnknownOpcodePolicy::Ignore),
+            "log" => Ok(UnknownOpcodePolicy::Log),
+            "halt" => Ok(UnknownOpcodePolicy::Halt),
+            _ => Err(format!("unknown opcode policy '{}'", s)),
+        }
+    }
+}
+
+/// Which CHIP-8 dialect the machine implements, each one extends the one before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Variant {
+    /// The original interpreter, 4 KiB of memory
+    Chip8,
+    /// SUPER-CHIP 1.1, 4 KiB of memory
+    SuperChip,
+    /// XO-CHIP, 64 KiB of memory and the `F000 nnnn` long load
+    XoChip,
+}
+
+impl Variant {
+    /// Quirks used when no profile is picked explicitly
+    pub fn default_quirks(self) -> Quirks {
+        match self {
+            Variant::Chip8 => Quirks::default(),
+            Variant::SuperChip => Quirks::SUPER_CHIP,
+            Variant::XoChip => Quirks::XO_CHIP,
+        }
+    }
+
+    /// Bytes of addressable memory
+    pub fn mem_size(self) -> usize {
+        match self {
+            Variant::Chip8 | Variant::SuperChip => MEM_SIZE,
+            Variant::XoChip => XO_MEM_SIZE,
+        }
+    }
+
+    /// The first dialect `opcode` is an instruction of
+    pub fn introducing(opcode: u16) -> Variant {
+        match (opcode >> 12, opcode & 0x0FFF) {
+            (0x0, 0x0C0..=0x0CF) | (0x0, 0x0FB..=0x0FF) => Variant::SuperChip,
+            (0xF, xkk) if matches!(xkk & 0xFF, 0x30 | 0x75 | 0x85) => Variant::SuperChip,
+            (0x5, xyn) if matches!(xyn & 0xF, 0x2 | 0x3) => Variant::XoChip,
+            (0xF, xkk) if matches!(xkk & 0xFF, 0x00 | 0x01 | 0x02 | 0x3A) => Variant::XoChip,
+            _ => Variant::Chip8,
+        }
+    }
+
+    /// Whether this dialect decodes `opcode`, the rest are unknown opcodes
+    pub fn supports(self, opcode: u16) -> bool {
+        Self::introducing(opcode) <= self
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chip8" => Ok(Variant::Chip8),
+            "schip" => Ok(Variant::SuperChip),
+            "xochip" => Ok(Variant::XoChip),
+            _ => Err(format!(
+                "unknown variant '{}' (expected chip8, schip or xochip)",
+                s
+            )),
+        }
+    }
+}
+
+/// An opcode that didn't decode, and where it was fetched from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnknownOpcode {
+    pub opcode: u16,
+    pub pc: u16,
+}
+
+/// Faults raised by a misbehaving rom, the machine should be halted when one is returned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Chip8Error {
+    /// `CALL` with all 16 stack entries in use
+    StackOverflow,
+    /// `RET` with an empty stack
+    StackUnderflow,
+    /// Read or write past the end of memory
+    MemoryOutOfBounds { addr: usize },
+    /// Opcode that doesn't decode to any instruction
+    InvalidOpcode { opcode: u16, pc: u16 },
+}
+
+impl fmt::Display for Chip8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Chip8Error::StackOverflow => write!(f, "stack overflow"),
+            Chip8Error::StackUnderflow => write!(f, "return with an empty stack"),
+            Chip8Error::MemoryOutOfBounds { addr } => {
+                write!(f, "memory access out of bounds at {:#05X}", addr)
+            }
+            Chip8Error::InvalidOpcode { opcode, pc } => {
+                write!(f, "invalid opcode {:04X} at {:#05X}", opcode, pc)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for Chip8Error {}
+
+/// Reasons a rom can't be loaded into memory
+#[derive(Debug)]
+pub enum RomError {
+    /// The rom file couldn't be read
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// The rom has no bytes to run
+    Empty,
+    /// The rom doesn't fit between the start address and the end of memory
+    TooLarge { size: usize, available: usize },
+}
+
+impl fmt::Display for RomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            RomError::Io(err) => write!(f, "failed to read rom: {}", err),
+            RomError::Empty => write!(f, "rom is empty"),
+            RomError::TooLarge { size, available } => write!(
+                f,
+                "rom is {} bytes but only {} bytes are available",
+                size, available
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RomError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RomError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for RomError {
+    fn from(err: io::Error) -> Self {
+        RomError::Io(err)
+    }
+}
+
+/// The machine, `R` supplies the random bytes of `Cxkk`.
+/// It takes about 71 KiB whatever the variant: 64 KiB of memory, the dispatch tables and the display.
+/// `new` and `with_rng` return it by value, `Box::new(Chip8::new())` keeps it off the stack
+#[allow(non_snake_case)]
+pub struct Chip8<R = DefaultRandom> {
+    pub memory: Memory,
+    pub registers: [u8; 16],
+    pub index: u16,
+    pub pc: u16,
+    pub stack: [u16; 16],
+    pub sp: u8,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub keypad: [u8; 16],
+    /// The display, with the SUPER-CHIP hires mode and the XO-CHIP second plane
+    pub video: Framebuffer,
+    /// XO-CHIP bitplanes drawn to and cleared, bit n selects plane n of `video`
+    pub planes: u8,
+    /// SUPER-CHIP user flags saved and restored by `Fx75`/`Fx85`
+    pub rpl_flags: [u8; 16],
+    /// XO-CHIP 1-bit audio pattern loaded by `F002`, the plain buzzer plays until one is loaded
+    pub audio_pattern: Option<[u8; 16]>,
+    /// XO-CHIP pattern playback pitch set by `Fx3A`, 64 is 4000 bits per second
+    pub pitch: u8,
+    pub opcode: u16,
+    /// Instructions executed per second by `run_frame`
+    pub speed: u32,
+    pub variant: Variant,
+    pub quirks: Quirks,
+    // instructions owed from previous frames when speed isn't a multiple of 60
+    pub(crate) cycle_remainder: u32,
+    // instructions left in the frame being run, 0 between frames
+    pub(crate) frame_cycles: u32,
+    pub unknown_opcode_policy: UnknownOpcodePolicy,
+    /// Most recent unknown opcode hit since the rom was loaded
+    pub last_unknown_opcode: Option<UnknownOpcode>,
+    /// Number of unknown opcodes hit since the rom was loaded
+    pub unknown_opcode_count: u32,
+    pub rng: R,
+    table: [Instruction<R>; 32],
+    table0: [Instruction<R>; 256],
+    table5: [Instruction<R>; 16],
+    table8: [Instruction<R>; 16],
+    tableE: [Instruction<R>; 16],
+    tableF: [Instruction<R>; 256],
+
+}
+
+impl Default for Chip8 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chip8 {
+    pub fn new() -> Self {
+        Self::with_rng(DefaultRandom::default())
+    }
+
+    /// Restart the random sequence from `seed`, the same seed gives the same `Cxkk` results
+    pub fn seed(&mut self, seed: u32) {
+        self.rng = XorShift::new(seed);
+    }
+}
+
+#[allow(non_snake_case)]
+impl<R: Random> Chip8<R> {
+    /// A machine drawing its random bytes from `rng`
+    pub fn with_rng(rng: R) -> Self {
+        let font_set: [u8; 80] = [
+            0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+            0x20, 0x60, 0x20, 0x20, 0x70, // 1
+            0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+            0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+            0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+            0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+            0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+            0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+            0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+            0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+            0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+            0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+            0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+            0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+            0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+            0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+        ];
+        // SUPER-CHIP 8x10 digits, A-F from XO-CHIP
+        let large_font_set: [u8; 160] = [
+            0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+            0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+            0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+            0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+            0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+            0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+            0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+            0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+            0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+            0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+            0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+            0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+            0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+            0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+            0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+            0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, // F
+        ];
+        // init empty memory
+        let mut memory: Memory = [0; XO_MEM_SIZE];
+
+        // load the font sets into memory
+        Self::load_font_set(&mut memory, FONT_SET_START_ADDRESS, &font_set);
+        Self::load_font_set(&mut memory, LARGE_FONT_SET_START_ADDRESS, &large_font_set);
+
+        // instructions tables
+        let mut table: [Instruction<R>; 32] = [Self::OP_NULL; 32];
+        let mut table0: [Instruction<R>; 256] = [Self::OP_NULL; 256];
+        let mut table5: [Instruction<R>; 16] = [Self::OP_NULL; 16];
+        let mut table8: [Instruction<R>; 16] = [Self::OP_NULL; 16];
+        let mut tableE: [Instruction<R>; 16] = [Self::OP_NULL; 16];
+        let mut tableF: [Instruction<R>; 256] = [Self::OP_NULL; 256];
+
+        table[0x0] = Chip8::table0;
+		table[0x1] = Chip8::OP_1nnn;
+		table[0x2] = Chip8::OP_2nnn;
+		table[0x3] = Chip8::OP_3xkk;
+		table[0x4] = Chip8::OP_4xkk;
+		table[0x5] = Chip8::table5;
+		table[0x6] = Chip8::OP_6xkk;
+		table[0x7] = Chip8::OP_7xkk;
+		table[0x8] = Chip8::table8;
+		table[0x9] = Chip8::OP_9xy0;
+		table[0xA] = Chip8::OP_Annn;
+		table[0xB] = Chip8::OP_Bnnn;
+		table[0xC] = Chip8::OP_Cxkk;
+		table[0xD] = Chip8::OP_Dxyn;
+		table[0xE] = Chip8::tableE;
+        table[0xF] = Chip8::tableF;
+        
+        table0[0xE0] = Chip8::OP_00E0;
+        table0[0xEE] = Chip8::OP_00EE;
+        for n in 0x0..=0xF {
+            table0[0xC0 + n] = Chip8::OP_00CN;
+        }
+        table0[0xFB] = Chip8::OP_00FB;
+        table0[0xFC] = Chip8::OP_00FC;
+        table0[0xFD] = Chip8::OP_00FD;
+        table0[0xFE] = Chip8::OP_00FE;
+        table0[0xFF] = Chip8::OP_00FF;
+        
+        table5[0x0] = Chip8::OP_5xy0;
+        table5[0x2] = Chip8::OP_5xy2;
+        table5[0x3] = Chip8::OP_5xy3;
+
+        table8[0x0] = Chip8::OP_8xy0;
+		table8[0x1] = Chip8::OP_8xy1;
+		table8[0x2] = Chip8::OP_8xy2;
+		table8[0x3] = Chip8::OP_8xy3;
+		table8[0x4] = Chip8::OP_8xy4;
+		table8[0x5] = Chip8::OP_8xy5;
+		table8[0x6] = Chip8::OP_8xy6;
+		table8[0x7] = Chip8::OP_8xy7;
+        table8[0xE] = Chip8::OP_8xyE;
+        
+        tableE[0x1] = Chip8::OP_ExA1;
+        tableE[0xE] = Chip8::OP_Ex9E;
+        
+        tableF[0x00] = Chip8::OP_F000;
+        tableF[0x01] = Chip8::OP_Fn01;
+        tableF[0x02] = Chip8::OP_F002;
+        tableF[0x07] = Chip8::OP_Fx07;
+		tableF[0x0A] = Chip8::OP_Fx0A;
+		tableF[0x15] = Chip8::OP_Fx15;
+		tableF[0x18] = Chip8::OP_Fx18;
+		tableF[0x1E] = Chip8::OP_Fx1E;
+		tableF[0x29] = Chip8::OP_Fx29;
+		tableF[0x30] = Chip8::OP_Fx30;
+		tableF[0x33] = Chip8::OP_Fx33;
+		tableF[0x55] = Chip8::OP_Fx55;
+        tableF[0x65] = Chip8::OP_Fx65;
+        tableF[0x75] = Chip8::OP_Fx75;
+        tableF[0x85] = Chip8::OP_Fx85;
+        tableF[0x3A] = Chip8::OP_Fx3A;
+
+
+        Chip8 {
+            memory,
+            registers: [0; 16],
+            index: 0,
+            pc: START_ADDRESS as u16,
+            stack: [0; 16],
+            sp: 0,
+            delay_timer: 0,
+            sound_timer: 0,
+            keypad: [0; 16],
+            video: Framebuffer::new(),
+            planes: 0b01,
+            rpl_flags: [0; 16],
+            audio_pattern: None,
+            pitch: 64,
+            opcode: 0,
+            speed: DEFAULT_SPEED,
+            variant: Variant::Chip8,
+            quirks: Quirks::default(),
+            cycle_remainder: 0,
+            frame_cycles: 0,
+            unknown_opcode_policy: UnknownOpcodePolicy::Halt,
+            last_unknown_opcode: None,
+            unknown_opcode_count: 0,
+            rng,
+            table,
+            table0,
+            table5,
+            table8,
+            tableE,
+            tableF,
+        }
+    }
+
+    /// Load a rom file into memory at the start address.
+    /// Returns the number of bytes loaded
+    #[cfg(feature = "std")]
+    pub fn load_rom<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, RomError> {
+        // load file to byte stream
+        let content: Vec<u8> = fs::read(path)?;
+
+        self.load_rom_bytes(&content)
+    }
+
+    /// Load rom bytes into memory at the start address.
+    /// Returns the number of bytes loaded
+    pub fn load_rom_bytes(&mut self, content: &[u8]) -> Result<usize, RomError> {
+        if content.is_empty() {
+            return Err(RomError::Empty);
+        }
+
+        let available = self.mem_size() - START_ADDRESS;
+        if content.len() > available {
+            return Err(RomError::TooLarge {
+                size: content.len(),
+                available,
+            });
+        }
+
+        // dump to memory
+        self.memory[START_ADDRESS..START_ADDRESS + content.len()].copy_from_slice(content);
+
+        // new run, new unknown opcode stats
+        self.last_unknown_opcode = None;
+        self.unknown_opcode_count = 0;
+
+        Ok(content.len())
+    }
+
+    /// Fetch, decode and execute one instruction.
+    /// A returned error means the rom faulted and the machine should stop
+    pub fn cycle(&mut self) -> Result<StepEvent, Chip8Error> {
+        // fetch
+        let pc = self.pc as usize;
+        self.check_memory(pc, 2)?;
+        let opcode: u16 = ((self.memory[pc] as u16) << 8) | (self.memory[pc + 1]) as u16;
+        self.opcode = opcode;
+
+        // increment pc before execute
+        self.pc = self.pc.wrapping_add(2);
+
+        // instructions of a later dialect than the variant's are unknown
+        if !self.variant.supports(opcode) {
+            return self.OP_NULL();
+        }
+
+        // decode and execute
+        self.table[((opcode & 0xF000) >> 12) as usize](self)
+    }
+
+    /// Run one 60 Hz frame: `speed / 60` instructions followed by one timer tick.
+    /// Returns the event of the last instruction executed
+    pub fn run_frame(&mut self) -> Result<StepEvent, Chip8Error> {
+        // a frame `step` left halfway is finished first
+        if self.frame_cycles == 0 {
+            self.begin_frame();
+        }
+
+        let mut event = StepEvent::Executed;
+        while self.frame_cycles > 0 {
+            event = self.frame_cycle()?;
+        }
+
+        self.tick_timers();
+
+        Ok(event)
+    }
+
+    /// Run one instruction of the frames `run_frame` runs, ticking the timers when it ends one.
+    /// For running a few instructions at a time, e.g. in the debugger, at the same pace as `run_frame`
+    pub fn step(&mut self) -> Result<StepEvent, Chip8Error> {
+        // below 60 ips some frames have no instruction, they only tick the timers
+        while self.frame_cycles == 0 && self.speed > 0 {
+            self.begin_frame();
+            if self.frame_cycles == 0 {
+                self.tick_timers();
+            }
+        }
+
+        let event = self.frame_cycle()?;
+        if self.frame_cycles == 0 {
+            self.tick_timers();
+        }
+
+        Ok(event)
+    }
+
+    /// Start a frame of `speed / 60` instructions
+    fn begin_frame(&mut self) {
+        // carry the leftover so e.g. 700 ips runs 11 or 12 instructions a frame,
+        // in 64 bits as any u32 speed is allowed
+        let budget = self.speed as u64 + self.cycle_remainder as u64;
+        self.frame_cycles = (budget / TIMER_HZ as u64) as u32;
+        self.cycle_remainder = (budget % TIMER_HZ as u64) as u32;
+    }
+
+    /// Run the next instruction of the frame, `frame_cycles` is 0 once the frame is over
+    fn frame_cycle(&mut self) -> Result<StepEvent, Chip8Error> {
+        let event = self.cycle()?;
+        self.frame_cycles = self.frame_cycles.saturating_sub(1);
+
+        // the sprite waits for vblank, nothing else runs this frame
+        if event == StepEvent::Drew && self.quirks.display_wait {
+            self.frame_cycles = 0;
+        }
+
+        if event == StepEvent::Exited {
+            self.frame_cycles = 0;
+        }
+
+        Ok(event)
+    }
+
+    /// True while the sound timer is running and the buzzer should sound
+    pub fn is_beeping(&self) -> bool {
+        self.sound_timer > 0
+    }
+
+    /// Count the delay and sound timers down by one, called at 60 Hz
+    pub fn tick_timers(&mut self) {
+        // decrement timer if set
+        if self.delay_timer > 0 {
+            self.delay_timer -= 1;
+        }
+
+        // decrement sound timer if set
+        if self.sound_timer > 0 {
+            self.sound_timer -= 1;
+        }
+    }
+
+    /// Bytes of memory the rom can address, depends on the variant
+    pub fn mem_size(&self) -> usize {
+        self.variant.mem_size()
+    }
+
+    /// Make sure `len` bytes starting at `addr` are inside memory
+    fn check_memory(&self, addr: usize, len: usize) -> Result<(), Chip8Error> {
+        let mem_size = self.mem_size();
+        if addr + len > mem_size {
+            return Err(Chip8Error::MemoryOutOfBounds {
+                addr: addr.max(mem_size),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Move I after `Fx55`/`Fx65` went through registers V0 to Vx
+    fn advance_index(&mut self, vx: u8) {
+        match self.quirks.load_store_index {
+            IndexIncrement::Unchanged => {}
+            IndexIncrement::PastLast => self.index = self.index.wrapping_add(vx as u16 + 1),
+            IndexIncrement::ByX => self.index = self.index.wrapping_add(vx as u16),
+        }
+    }
+
+    /// Skip the next instruction, which is 4 bytes long when it's XO-CHIP's `F000 nnnn`
+    fn skip_next(&mut self) {
+        let pc = self.pc as usize;
+        let long_load = self.variant.supports(0xF000)
+            && pc + 1 < self.mem_size()
+            && self.memory[pc] == 0xF0
+            && self.memory[pc + 1] == 0x00;
+
+        self.pc = self.pc.wrapping_add(if long_load { 4 } else { 2 });
+    }
+
+    /// Color of a pixel in the current video mode, 0 to 3 with bit n set when it's lit in plane n + 1
+    pub fn color_index(&self, x: usize, y: usize) -> usize {
+        self.video.get(x, y)
+    }
+
+    fn load_font_set(memory: &mut Memory, address: usize, font_set: &[u8]) {
+        // dump font set to memory
+        for (x, byte) in font_set.iter().enumerate() {
+            memory[address + x] = *byte
+        }
+    }
+
+    /// Width in pixels of the current video mode
+    pub fn width(&self) -> usize {
+        self.video.width()
+    }
+
+    /// Height in pixels of the current video mode
+    pub fn height(&self) -> usize {
+        self.video.height()
+    }
+
+    // instructions tables
+
+    fn table0(&mut self) -> OpResult {
+        // 0nnn machine code calls aren't supported, only 00nn is decoded
+        if self.opcode & 0x0F00 != 0 {
+            return self.OP_NULL();
+        }
+
+        let instruct = self.table0[(self.opcode & 0x00FF) as usize];
+        instruct(self)
+    }
+    fn table5(&mut self) -> OpResult {
+        let instruct = self.table5[(self.opcode & 0x000F) as usize];
+        instruct(self)
+    }
+    fn table8(&mut self) -> OpResult {
+        let instruct = self.table8[(self.opcode & 0x000F) as usize];
+        instruct(self)
+    }
+    fn tableE(&mut self) -> OpResult {
+        let instruct = self.tableE[(self.opcode & 0x000F) as usize];
+        instruct(self)
+    }
+    fn tableF(&mut self) -> OpResult {
+        let instruct = self.tableF[(self.opcode & 0x00FF) as usize];
+        instruct(self)
+    }
+    fn OP_NULL(&mut self) -> OpResult {
+        let unknown = UnknownOpcode {
+            opcode: self.opcode,
+            pc: self.pc.wrapping_sub(2),
+        };
+
+        self.last_unknown_opcode = Some(unknown);
+        self.unknown_opcode_count = self.unknown_opcode_count.saturating_add(1);
+
+        match self.unknown_opcode_policy {
+            UnknownOpcodePolicy::Ignore => Ok(StepEvent::UnknownOpcode),
+            UnknownOpcodePolicy::Log => {
+                #[cfg(feature = "std")]
+                eprintln!("unknown opcode {:04X} at {:#05X}", unknown.opcode, unknown.pc);
+                Ok(StepEvent::UnknownOpcode)
+            }
+            UnknownOpcodePolicy::Halt => Err(Chip8Error::InvalidOpcode {
+                opcode: unknown.opcode,
+                pc: unknown.pc,
+            }),
+        }
+    }
+}
+
+// Instructions
+// note: AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA -rain
+// finished, now lets descend into testing hell -rain
+#[allow(non_snake_case)]
+impl<R: Random> Chip8<R> {
+    /// CLS,
+    /// Clear the selected planes
+    pub fn OP_00E0(&mut self) -> OpResult {
+        self.video.clear(self.planes);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// RET,
+    /// Return to previous stack
+    pub fn OP_00EE(&mut self) -> OpResult {
+        if self.sp == 0 {
+            return Err(Chip8Error::StackUnderflow);
+        }
+
+        self.sp -= 1;
+        self.pc = self.stack[self.sp as usize];
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// JMP addr,
+    /// Jump to nnn, no stacking
+    pub fn OP_1nnn(&mut self) -> OpResult {
+        let address: u16 = self.opcode & 0x0FFF;
+        self.pc = address;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// CALL addr,
+    /// Call subroutine at nnn
+    pub fn OP_2nnn(&mut self) -> OpResult {
+        let address: u16 = self.opcode & 0x0FFF;
+
+        if self.sp as usize >= self.stack.len() {
+            return Err(Chip8Error::StackOverflow);
+        }
+
+        self.stack[self.sp as usize] = self.pc;
+        self.sp += 1;
+        self.pc = address;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SE Vx, byte,
+    /// Skip next instruction if Vx == kk
+    pub fn OP_3xkk(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let byte: u8 = (self.opcode & 0x00FF) as u8;
+
+        if self.registers[vx as usize] == byte {
+            self.skip_next();
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SNE Vx, byte,
+    /// Skip next instruction if Vx != kk
+    pub fn OP_4xkk(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let byte: u8 = (self.opcode & 0x00FF) as u8;
+
+        if self.registers[vx as usize] != byte {
+            self.skip_next();
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SE Vx, Vy,
+    /// Skip next instruction if Vx == Vy
+    pub fn OP_5xy0(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        if self.registers[vx as usize] == self.registers[vy as usize] {
+            self.skip_next();
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD Vx, byte,
+    /// Set Vx = kk
+    pub fn OP_6xkk(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let byte: u8 = (self.opcode & 0x00FF) as u8;
+
+        self.registers[vx as usize] = byte;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// ADD Vx, byte,
+    /// Set Vx = Vx + kk
+    pub fn OP_7xkk(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let byte: u8 = (self.opcode & 0x00FF) as u8;
+
+        // println!("7xkk opcode = {:X} vx: {} | byte: {}",self.opcode, self.registers[vx as usize], byte);
+
+        // carry is discarded, Vf is left alone
+        self.registers[vx as usize] = self.registers[vx as usize].wrapping_add(byte);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD Vx, Vy,
+    /// Set Vx = Vy
+    pub fn OP_8xy0(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        let val = self.registers[vy as usize];
+        self.registers[vx as usize] = val;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// OR Vx, Vy,
+    /// Set Vx | Vy
+    pub fn OP_8xy1(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        self.registers[vx as usize] |= self.registers[vy as usize];
+
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// AND Vx, Vy,
+    /// Set Vx & Vy
+    pub fn OP_8xy2(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        self.registers[vx as usize] &= self.registers[vy as usize];
+
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// XOR Vx, Vy,
+    /// Set Vx & Vy
+    pub fn OP_8xy3(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        self.registers[vx as usize] ^= self.registers[vy as usize];
+
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// ADD Vx, Vy, set Vf = Carry,
+    /// ADD Vx and Vy. if the result greater than 8bit (>255) set Vf to 1, otherwise Vf to 0.
+    /// Store the lower 8 bits to Vx
+    pub fn OP_8xy4(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+        let sum: u16 = self.registers[vx as usize] as u16 + self.registers[vy as usize] as u16;
+
+        if sum > 0xFF {
+            self.registers[0xF] = 1; // register 0xf = Vf
+        } else {
+            self.registers[0xF] = 0; // register 0xf = Vf
+        }
+
+        self.registers[vx as usize] = (sum & 0xFF) as u8;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SUB Vx, Vy, set Vf = NOT Borrow.
+    /// Set Vx -= Vy, if Vx > Vy set Vf to 1 otherwise 0.
+    /// Store result in Vx
+    pub fn OP_8xy5(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        // register 0xf = Vf
+        if self.registers[vx as usize] > self.registers[vy as usize] {
+            self.registers[0xF] = 1;
+        } else {
+            self.registers[0xF] = 0;
+        }
+
+        self.registers[vx as usize] = self.registers[vx as usize].wrapping_sub(self.registers[vy as usize]);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SHR Vx {, Vy}
+    /// Set Vx = Vx >> 1, or Vx = Vy >> 1 with the `shift_uses_vy` quirk
+    /// if Vx is 1, then Vf is set to 1 otherwise 0
+    pub fn OP_8xy6(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        if self.quirks.shift_uses_vy {
+            self.registers[vx as usize] = self.registers[vy as usize];
+        }
+
+        // the flag is written last, so it wins when Vx is Vf
+        let flag = self.registers[vx as usize] & 0x1;
+        self.registers[vx as usize] >>= 1;
+        self.registers[0xF] = flag;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SUBN Vx, Vy, set Vf = NOT Borrow.
+    /// Set Vy -= Vx, if Vx < Vy set Vf to 1 otherwise 0.
+    /// Store result in Vx
+    pub fn OP_8xy7(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        // register 0xf = Vf
+        if self.registers[vx as usize] < self.registers[vy as usize] {
+            self.registers[0xF] = 1;
+        } else {
+            self.registers[0xF] = 0;
+        }
+
+        self.registers[vx as usize] = self.registers[vy as usize].wrapping_sub(self.registers[vx as usize]);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SHL Vx {, Vy}.
+    /// Set Vx = Vx << 1, or Vx = Vy << 1 with the `shift_uses_vy` quirk.
+    /// If the most-significant bit of Vx is 1, then VF is set to 1, otherwise to 0.
+    pub fn OP_8xyE(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        if self.quirks.shift_uses_vy {
+            self.registers[vx as usize] = self.registers[vy as usize];
+        }
+
+        // the flag is written last, so it wins when Vx is Vf
+        let flag = (self.registers[vx as usize] & 0x80) >> 7;
+        self.registers[vx as usize] <<= 1;
+        self.registers[0xF] = flag;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SNE Vx, Vy.
+    ///* Skip next instruction if Vx != Vy.
+    pub fn OP_9xy0(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+
+        if self.registers[vx as usize] != self.registers[vy as usize] {
+            self.skip_next();
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD I, addr
+    ///* Set I = addr
+    pub fn OP_Annn(&mut self) -> OpResult {
+        let address: u16 = self.opcode & 0x0FFF;
+
+        self.index = address;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// JP V0, addr
+    ///* jump to location nnn + V0, or xnn + Vx with the `jump_uses_vx` quirk
+    pub fn OP_Bnnn(&mut self) -> OpResult {
+        let address: u16 = self.opcode & 0x0FFF;
+        let vx: usize = if self.quirks.jump_uses_vx {
+            ((self.opcode & 0x0F00) >> 8) as usize
+        } else {
+            0
+        };
+
+        self.pc = (self.registers[vx] as u16) + address;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// RND Vx, byte
+    ///* Set Vx = random byte + kk
+    pub fn OP_Cxkk(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let byte: u8 = (self.opcode & 0x00FF) as u8;
+
+        self.registers[vx as usize] = self.rng.next_byte() & byte;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// DRW Vx, Vy, nibble
+    ///* Display n-byte sprite starting at memory location I at (Vx, Vy), set VF = collision.
+    ///* With n = 0 a 16x16 sprite is drawn from 32 bytes, two bytes per row (SUPER-CHIP)
+    ///* With both XO-CHIP planes selected the second plane's sprite follows the first one in memory
+    ///* The top left corner wraps around the screen, the rest of the sprite follows `quirks.edge_mode`
+    pub fn OP_Dxyn(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let vy: u8 = ((self.opcode & 0x00F0) >> 4) as u8;
+        let height: u8 = (self.opcode & 0x000F) as u8;
+
+        // Dxy0 is 16 pixels wide and 16 rows tall
+        let (rows, cols): (usize, usize) = if height == 0 {
+            (16, 16)
+        } else {
+            (height as usize, 8)
+        };
+        let sprite_size = rows * cols / 8;
+
+        // read before VF is reset, a sprite can be drawn at (VF, VF)
+        let xPos: usize = self.registers[vx as usize] as usize % self.width();
+        let yPos: usize = self.registers[vy as usize] as usize % self.height();
+
+        // reset flag
+        self.registers[0xF] = 0;
+
+        let plane_count = (self.planes & 0b01 != 0) as usize + (self.planes & 0b10 != 0) as usize;
+        self.check_memory(self.index as usize, sprite_size * plane_count)?;
+
+        let mut address = self.index as usize;
+        for plane in 0..2 {
+            if self.planes & (1 << plane) == 0 {
+                continue;
+            }
+
+            if self.draw_sprite_plane(plane, address, xPos, yPos, rows, cols) {
+                self.registers[0xF] = 1;
+            }
+            address += sprite_size;
+        }
+
+        Ok(StepEvent::Drew)
+    }
+
+    /// XOR one `rows` x `cols` sprite read at `address` into a bitplane with its top left corner at
+    /// (`xPos`, `yPos`), which must be on screen. True when a lit pixel was turned off
+    fn draw_sprite_plane(&mut self, plane: usize, address: usize, xPos: usize, yPos: usize, rows: usize, cols: usize) -> bool {
+        let width = self.width();
+        let video_height = self.height();
+        let bytes_per_row = cols / 8;
+        let edge_mode = self.quirks.edge_mode;
+        let mut collision = false;
+
+        for row in 0..rows {
+            let y = match (yPos + row, edge_mode) {
+                (y, _) if y < video_height => y,
+                (y, EdgeMode::Wrap) => y % video_height,
+                (_, EdgeMode::Clip) => break,
+            };
+
+            let address = address + row * bytes_per_row;
+            // left aligned in 16 bits so both sprite widths share the loop below
+            let spriteRow: u16 = if bytes_per_row == 2 {
+                ((self.memory[address] as u16) << 8) | self.memory[address + 1] as u16
+            } else {
+                (self.memory[address] as u16) << 8
+            };
+
+            let mut bits: u128 = 0;
+            for col in 0..cols {
+                if spriteRow & (0x8000 >> col) == 0 {
+                    continue;
+                }
+
+                let x = match (xPos + col, edge_mode) {
+                    (x, _) if x < width => x,
+                    (x, EdgeMode::Wrap) => x % width,
+                    (_, EdgeMode::Clip) => break,
+                };
+                bits |= framebuffer::bit(x);
+            }
+
+            if self.video.xor_row(plane, y, bits) {
+                collision = true;
+            }
+        }
+
+        collision
+    }
+
+    /// SKP Vx
+    ///* Skip next instruction if key with the value of Vx is pressed.
+    pub fn OP_Ex9E(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let key: u8 = self.registers[vx as usize] & 0xF;
+
+        if self.keypad[key as usize] == 1 {
+            self.skip_next();
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SKNP Vx
+    ///* Skip next instruction if key with the value of Vx is not pressed.
+    pub fn OP_ExA1(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let key: u8 = self.registers[vx as usize] & 0xF;
+
+        if self.keypad[key as usize] != 1 {
+            self.skip_next();
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD Vx, DT
+    ///* Set Vx = Delay Timer
+    pub fn OP_Fx07(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        self.registers[vx as usize] = self.delay_timer;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD Vx, K
+    ///* Wait for a key press, store value of the key to Vx
+    pub fn OP_Fx0A(&mut self) -> OpResult {
+        let vx: usize = ((self.opcode & 0x0F00) >> 8) as usize;
+
+        let mut pressedKey: u8 = 16; //not pressed
+
+        for x in 0..self.keypad.len() {
+            if self.keypad[x] == 1 {
+                pressedKey = x as u8;
+                break;
+            }
+        }
+
+        if pressedKey < 16 {
+            // if key pressed
+            self.registers[vx] = pressedKey;
+        } else {
+            self.pc = self.pc.wrapping_sub(2);
+            return Ok(StepEvent::WaitingForKey);
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD DT, Vx
+    ///* Set Delay Timer = Vx
+    pub fn OP_Fx15(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        self.delay_timer = self.registers[vx as usize];
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD ST, Vx
+    ///* Set Sound Timer = Vx
+    pub fn OP_Fx18(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+
+        self.sound_timer = self.registers[vx as usize];
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// ADD I, Vx
+    ///* Set I = I + Vx
+    pub fn OP_Fx1E(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+
+        self.index = self.index.wrapping_add(self.registers[vx as usize] as u16);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD F, Vx
+    ///* Set I = location of sprite for digit Vx
+    pub fn OP_Fx29(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let digit: u8 = self.registers[vx as usize];
+
+        self.index = (FONT_SET_START_ADDRESS + (5 * digit as usize)) as u16;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD B, Vx
+    ///* Store BCD representation of Vx in memory locations I, I+1, and I+2.
+    ///* The interpreter takes the decimal value of Vx, and places the hundreds digit in memory at location in I, the tens digit at location I+1, and the ones digit at location I+2.
+    pub fn OP_Fx33(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let mut val: u8 = self.registers[vx as usize];
+
+        self.check_memory(self.index as usize, 3)?;
+
+        // Ones
+        self.memory[(self.index + 2) as usize] = val % 10;
+        val /= 10;
+
+        // Tens
+        self.memory[(self.index + 1) as usize] = val % 10;
+        val /= 10;
+
+        // Hundreds
+        self.memory[(self.index) as usize] = val % 10;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD [I], Vx
+    ///* Store registers from V0 to Vx in memory starting at I
+    ///* I is then moved according to the `load_store_index` quirk
+    pub fn OP_Fx55(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+
+        self.check_memory(self.index as usize, vx as usize + 1)?;
+
+        for i in 0..vx + 1 {
+            self.memory[(self.index + (i as u16)) as usize] = self.registers[i as usize];
+        }
+
+        self.advance_index(vx);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD Vx, [I]
+    ///* Read registers from V0 to Vx from memory starting at I
+    ///* I is then moved according to the `load_store_index` quirk
+    pub fn OP_Fx65(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+
+        self.check_memory(self.index as usize, vx as usize + 1)?;
+
+        for x in 0..vx + 1{
+            self.registers[x as usize] = self.memory[(self.index + x as u16) as usize];
+        }
+
+        self.advance_index(vx);
+
+        Ok(StepEvent::Executed)
+    }
+}
+
+// SUPER-CHIP 1.1 instructions
+#[allow(non_snake_case)]
+impl<R: Random> Chip8<R> {
+    /// SCD nibble
+    ///* Scroll the selected planes down by n pixels
+    pub fn OP_00CN(&mut self) -> OpResult {
+        let n: usize = (self.opcode & 0x000F) as usize;
+        self.video.scroll_down(self.planes, n);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SCR
+    ///* Scroll the selected planes right by 4 pixels
+    pub fn OP_00FB(&mut self) -> OpResult {
+        self.video.scroll_right(self.planes, 4);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// SCL
+    ///* Scroll the selected planes left by 4 pixels
+    pub fn OP_00FC(&mut self) -> OpResult {
+        self.video.scroll_left(self.planes, 4);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// EXIT
+    ///* Stop the interpreter, the pc stays on this instruction
+    pub fn OP_00FD(&mut self) -> OpResult {
+        self.pc = self.pc.wrapping_sub(2);
+
+        Ok(StepEvent::Exited)
+    }
+
+    /// LOW
+    ///* Switch to 64x32 and clear both planes
+    pub fn OP_00FE(&mut self) -> OpResult {
+        self.video.set_hires(false);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// HIGH
+    ///* Switch to 128x64 and clear both planes
+    pub fn OP_00FF(&mut self) -> OpResult {
+        self.video.set_hires(true);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD HF, Vx
+    ///* Set I = location of the 8x10 sprite for digit Vx
+    pub fn OP_Fx30(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+        let digit: u8 = self.registers[vx as usize] & 0xF;
+
+        self.index = (LARGE_FONT_SET_START_ADDRESS + (10 * digit as usize)) as u16;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD R, Vx
+    ///* Store V0 to Vx in the user flags
+    pub fn OP_Fx75(&mut self) -> OpResult {
+        let vx: usize = ((self.opcode & 0x0F00) >> 8) as usize;
+
+        self.rpl_flags[..=vx].copy_from_slice(&self.registers[..=vx]);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD Vx, R
+    ///* Read V0 to Vx from the user flags
+    pub fn OP_Fx85(&mut self) -> OpResult {
+        let vx: usize = ((self.opcode & 0x0F00) >> 8) as usize;
+
+        self.registers[..=vx].copy_from_slice(&self.rpl_flags[..=vx]);
+
+        Ok(StepEvent::Executed)
+    }
+}
+
+// XO-CHIP instructions
+#[allow(non_snake_case)]
+impl<R: Random> Chip8<R> {
+    /// SAVE Vx - Vy
+    ///* Store Vx to Vy in memory starting at I, in reverse order when x > y. I is left alone
+    pub fn OP_5xy2(&mut self) -> OpResult {
+        let vx: usize = ((self.opcode & 0x0F00) >> 8) as usize;
+        let vy: usize = ((self.opcode & 0x00F0) >> 4) as usize;
+        let count = vx.max(vy) - vx.min(vy) + 1;
+
+        self.check_memory(self.index as usize, count)?;
+
+        for offset in 0..count {
+            let register = if vx <= vy { vx + offset } else { vx - offset };
+            self.memory[self.index as usize + offset] = self.registers[register];
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LOAD Vx - Vy
+    ///* Read Vx to Vy from memory starting at I, in reverse order when x > y. I is left alone
+    pub fn OP_5xy3(&mut self) -> OpResult {
+        let vx: usize = ((self.opcode & 0x0F00) >> 8) as usize;
+        let vy: usize = ((self.opcode & 0x00F0) >> 4) as usize;
+        let count = vx.max(vy) - vx.min(vy) + 1;
+
+        self.check_memory(self.index as usize, count)?;
+
+        for offset in 0..count {
+            let register = if vx <= vy { vx + offset } else { vx - offset };
+            self.registers[register] = self.memory[self.index as usize + offset];
+        }
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// LD I, long addr
+    ///* Set I = the 16-bit address in the next two bytes, which are skipped
+    pub fn OP_F000(&mut self) -> OpResult {
+        if self.opcode != 0xF000 {
+            return self.OP_NULL();
+        }
+
+        let pc = self.pc as usize;
+        self.check_memory(pc, 2)?;
+
+        self.index = ((self.memory[pc] as u16) << 8) | self.memory[pc + 1] as u16;
+        self.pc = self.pc.wrapping_add(2);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// PLANE n
+    ///* Select the bitplanes drawn to and cleared, n is a 2-bit mask
+    pub fn OP_Fn01(&mut self) -> OpResult {
+        let n: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+
+        self.planes = n & 0b11;
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// AUDIO
+    ///* Load the 16-byte audio pattern from memory starting at I
+    pub fn OP_F002(&mut self) -> OpResult {
+        if self.opcode != 0xF002 {
+            return self.OP_NULL();
+        }
+
+        let index = self.index as usize;
+        self.check_memory(index, 16)?;
+
+        let mut pattern = [0; 16];
+        pattern.copy_from_slice(&self.memory[index..index + 16]);
+        self.audio_pattern = Some(pattern);
+
+        Ok(StepEvent::Executed)
+    }
+
+    /// PITCH Vx
+    ///* Set the audio pattern playback pitch = Vx
+    pub fn OP_Fx3A(&mut self) -> OpResult {
+        let vx: u8 = ((self.opcode & 0x0F00) >> 8) as u8;
+
+        self.pitch = self.registers[vx as usize];
+
+        Ok(StepEvent::Executed)
+    }
+}
@@ -0,0 +1,356 @@
+// Debugger: breakpoints, watchpoints and stepping on top of a `Chip8`
+use crate::chip8::{Chip8, Chip8Error, StepEvent};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Comparison used by conditional breakpoints
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// `Vx <op> value`, checked when the breakpoint address is reached
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub register: usize,
+    pub op: CompareOp,
+    pub value: u8,
+}
+
+impl Condition {
+    pub fn matches(&self, chip: &Chip8) -> bool {
+        let register = chip.registers[self.register];
+        match self.op {
+            CompareOp::Eq => register == self.value,
+            CompareOp::Ne => register != self.value,
+            CompareOp::Lt => register < self.value,
+            CompareOp::Le => register <= self.value,
+            CompareOp::Gt => register > self.value,
+            CompareOp::Ge => register >= self.value,
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    /// Parse `V3 == 10`, `vA >= 0x20`... spaces around the operator are optional
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let ops = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+
+        let (position, symbol, op) = ops
+            .iter()
+            .filter_map(|(symbol, op)| s.find(symbol).map(|position| (position, *symbol, *op)))
+            .min_by_key(|(position, _, _)| *position)
+            .ok_or_else(|| format!("no comparison in condition '{}'", s))?;
+
+        let register = s[..position].trim();
+        let register = register
+            .strip_prefix('V')
+            .or_else(|| register.strip_prefix('v'))
+            .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+            .filter(|digit| *digit < 16)
+            .ok_or_else(|| format!("'{}' is not a register (V0-VF)", register))?;
+        let value = parse_value(s[position + symbol.len()..].trim())?;
+        let value = u8::try_from(value).map_err(|_| format!("{} doesn't fit in a register", value))?;
+
+        Ok(Condition {
+            register: register as usize,
+            op,
+            value,
+        })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self.op {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        };
+        write!(f, "V{:X} {} {:#04X}", self.register, op, self.value)
+    }
+}
+
+/// Parse a decimal number or a `0x` prefixed hex one
+pub fn parse_value(s: &str) -> Result<u32, String> {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("'{}' is not a number", s))
+}
+
+/// Stop when the pc reaches `addr`, and `condition` holds if there is one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Breakpoint {
+    pub addr: u16,
+    pub condition: Option<Condition>,
+}
+
+/// Stop after an instruction writes to `addr..addr + len`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Watchpoint {
+    pub addr: u16,
+    pub len: u16,
+}
+
+/// Why the debugger handed control back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stop {
+    /// The requested steps or cycles ran without anything to report
+    Done,
+    /// The pc reached a breakpoint, the instruction there hasn't run yet
+    Breakpoint(u16),
+    /// The instruction at `pc` wrote to watched memory at `addr`
+    Watchpoint { addr: u16, pc: u16 },
+    /// The rom quit with `00FD`
+    Exited,
+    /// The rom faulted, the machine can't go on
+    Fault(Chip8Error),
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stop::Done => write!(f, "stopped"),
+            Stop::Breakpoint(addr) => write!(f, "breakpoint at {:#05X}", addr),
+            Stop::Watchpoint { addr, pc } => {
+                write!(f, "watchpoint: {:#05X} written by the instruction at {:#05X}", addr, pc)
+            }
+            Stop::Exited => write!(f, "rom exited"),
+            Stop::Fault(err) => write!(f, "machine halted: {}", err),
+        }
+    }
+}
+
+/// Wraps a `Chip8` to run it under breakpoints and watchpoints.
+/// Instructions run through `Chip8::step`, so timers tick and sprites wait for vblank as in `Chip8::run_frame`
+pub struct Debugger {
+    pub chip: Chip8,
+    breakpoints: Vec<Breakpoint>,
+    watchpoints: Vec<Watchpoint>,
+    paused: bool,
+}
+
+impl Debugger {
+    pub fn new(chip: Chip8) -> Self {
+        Debugger {
+            chip,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            paused: false,
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Paused by `pause` or by the last stop, `run_frame` does nothing until `resume`
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Break at `addr`, replacing any breakpoint already there
+    pub fn add_breakpoint(&mut self, addr: u16) {
+        self.add_conditional_breakpoint(addr, None);
+    }
+
+    /// Break at `addr` only when `condition` holds
+    pub fn add_conditional_breakpoint(&mut self, addr: u16, condition: Option<Condition>) {
+        self.remove_breakpoint(addr);
+        self.breakpoints.push(Breakpoint { addr, condition });
+    }
+
+    /// Returns false if there was no breakpoint at `addr`
+    pub fn remove_breakpoint(&mut self, addr: u16) -> bool {
+        let count = self.breakpoints.len();
+        self.breakpoints.retain(|breakpoint| breakpoint.addr != addr);
+        self.breakpoints.len() != count
+    }
+
+    pub fn add_watchpoint(&mut self, addr: u16, len: u16) {
+        self.watchpoints.push(Watchpoint { addr, len: len.max(1) });
+    }
+
+    /// Returns false if no watchpoint starts at `addr`
+    pub fn remove_watchpoint(&mut self, addr: u16) -> bool {
+        let count = self.watchpoints.len();
+        self.watchpoints.retain(|watchpoint| watchpoint.addr != addr);
+        self.watchpoints.len() != count
+    }
+
+    /// Run exactly one instruction, a breakpoint on it is ignored
+    pub fn step(&mut self) -> Stop {
+        self.run_until(1, |_| true)
+    }
+
+    /// Like `step`, but a `2nnn` call runs until the subroutine returns
+    pub fn step_over(&mut self, max_cycles: u64) -> Stop {
+        let pc = self.chip.pc;
+        if self.opcode_at(pc) & 0xF000 != 0x2000 {
+            return self.step();
+        }
+
+        let sp = self.chip.sp;
+        let return_to = pc.wrapping_add(2);
+        self.run_until(max_cycles, |chip| chip.sp == sp && chip.pc == return_to)
+    }
+
+    /// Run until the current subroutine returns with `00EE`.
+    /// Outside of a subroutine there's nothing to return from, nothing runs
+    pub fn step_out(&mut self, max_cycles: u64) -> Result<Stop, String> {
+        let sp = self.chip.sp;
+        if sp == 0 {
+            return Err(String::from("not in a subroutine"));
+        }
+
+        Ok(self.run_until(max_cycles, |chip| chip.sp < sp))
+    }
+
+    /// Run until a breakpoint, watchpoint, exit or fault, at most `max_cycles` instructions
+    pub fn cont(&mut self, max_cycles: u64) -> Stop {
+        self.resume();
+        self.run_until(max_cycles, |_| false)
+    }
+
+    /// The rest of the machine's 60 Hz frame unless paused, for frontends driving the debugger.
+    /// Any stop other than `Done` pauses the debugger
+    pub fn run_frame(&mut self) -> Stop {
+        if self.paused {
+            return Stop::Done;
+        }
+
+        self.run_until(u64::MAX, |chip| chip.frame_cycles == 0)
+    }
+
+    /// Run instructions until `done` holds after one of them, something stops the machine
+    /// or `max_cycles` ran. The first instruction ignores breakpoints so a stopped machine can go on
+    pub fn run_until<F: Fn(&Chip8) -> bool>(&mut self, max_cycles: u64, done: F) -> Stop {
+        for cycle in 0..max_cycles {
+            let pc = self.chip.pc;
+
+            if cycle > 0 && self.breakpoint_hit(pc) {
+                self.paused = true;
+                return Stop::Breakpoint(pc);
+            }
+
+            let written = self.pending_write();
+            let event = match self.chip.step() {
+                Ok(event) => event,
+                Err(err) => {
+                    self.paused = true;
+                    return Stop::Fault(err);
+                }
+            };
+
+            if event == StepEvent::Exited {
+                self.paused = true;
+                return Stop::Exited;
+            }
+
+            if let Some((start, len)) = written {
+                if let Some(addr) = self.watched(start, len) {
+                    self.paused = true;
+                    return Stop::Watchpoint { addr, pc };
+                }
+            }
+
+            if done(&self.chip) {
+                return Stop::Done;
+            }
+        }
+
+        Stop::Done
+    }
+
+    fn opcode_at(&self, addr: u16) -> u16 {
+        let addr = addr as usize;
+        if addr + 1 >= self.chip.mem_size() {
+            return 0;
+        }
+        ((self.chip.memory[addr] as u16) << 8) | self.chip.memory[addr + 1] as u16
+    }
+
+    fn breakpoint_hit(&self, pc: u16) -> bool {
+        self.breakpoints.iter().any(|breakpoint| {
+            breakpoint.addr == pc
+                && breakpoint
+                    .condition
+                    .is_none_or(|condition| condition.matches(&self.chip))
+        })
+    }
+
+    /// Memory range the instruction at the pc is about to write, decoded before it runs
+    fn pending_write(&self) -> Option<(usize, usize)> {
+        let opcode = self.opcode_at(self.chip.pc);
+        // an opcode the variant doesn't have writes nothing
+        if !self.chip.variant.supports(opcode) {
+            return None;
+        }
+        let x = ((opcode & 0x0F00) >> 8) as usize;
+        let y = ((opcode & 0x00F0) >> 4) as usize;
+        let index = self.chip.index as usize;
+
+        match opcode & 0xF0FF {
+            0xF033 => return Some((index, 3)),
+            0xF055 => return Some((index, x + 1)),
+            _ => {}
+        }
+
+        // XO-CHIP save range
+        if opcode & 0xF00F == 0x5002 {
+            return Some((index, x.max(y) - x.min(y) + 1));
+        }
+
+        None
+    }
+
+    /// First watched address in `start..start + len`
+    fn watched(&self, start: usize, len: usize) -> Option<u16> {
+        self.watchpoints
+            .iter()
+            .filter_map(|watchpoint| {
+                let watch_start = watchpoint.addr as usize;
+                let watch_end = watch_start + watchpoint.len as usize;
+                let first = start.max(watch_start);
+                if first < (start + len).min(watch_end) {
+                    Some(first as u16)
+                } else {
+                    None
+                }
+            })
+            .min()
+    }
+}
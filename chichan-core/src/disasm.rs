@@ -0,0 +1,379 @@
+// Disassembler for CHIP-8, SUPER-CHIP and XO-CHIP roms
+use crate::chip8::Variant;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Where roms are loaded, and where disassembly starts tracing code
+pub const START_ADDRESS: u16 = 0x200;
+
+// bytes per `db` line in data regions
+const DATA_PER_LINE: usize = 8;
+
+/// An instruction argument
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operand {
+    /// `Vx`
+    Register(u8),
+    /// `Vx - Vy` register range of XO-CHIP's SAVE and LOAD
+    Range(u8, u8),
+    /// 8-bit immediate
+    Byte(u8),
+    /// 4-bit immediate
+    Nibble(u8),
+    /// 12-bit address, or 16-bit with `LD I, long`
+    Address(u16),
+    /// `long nnnn`, the 16-bit address of XO-CHIP's `F000 nnnn`
+    LongAddress(u16),
+    /// Fixed operand like `I`, `[I]`, `DT`, `K` or the `V0` of `JP V0, addr`
+    Keyword(&'static str),
+}
+
+/// How an instruction passes control on, used to tell code from data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flow {
+    /// Falls through to the next instruction
+    Next,
+    /// May skip the next instruction
+    Skip,
+    /// Jumps to the address
+    Jump(u16),
+    /// Calls the address then carries on with the next instruction
+    Call(u16),
+    /// Nothing statically known follows: RET, EXIT or a computed jump
+    Stop,
+}
+
+/// A decoded instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Op {
+    pub opcode: u16,
+    /// Second word of `F000 nnnn`
+    pub long: Option<u16>,
+    pub mnemonic: &'static str,
+    pub operands: Vec<Operand>,
+    pub flow: Flow,
+}
+
+impl Op {
+    /// Size in bytes, 4 for `F000 nnnn` and 2 otherwise
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        if self.long.is_some() {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// Address the instruction refers to: jump and call targets or the value loaded into I
+    pub fn target(&self) -> Option<u16> {
+        self.operands.iter().find_map(|operand| match operand {
+            Operand::Address(addr) | Operand::LongAddress(addr) => Some(*addr),
+            _ => None,
+        })
+    }
+
+    /// Assembly text, with addresses replaced by their label when `labels` has one
+    pub fn render(&self, labels: &BTreeMap<u16, String>) -> String {
+        let operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Register(x) => format!("V{:X}", x),
+                Operand::Range(x, y) => format!("V{:X} - V{:X}", x, y),
+                Operand::Byte(byte) => format!("{:#04X}", byte),
+                Operand::Nibble(n) => format!("{}", n),
+                Operand::Address(addr) => address(*addr, labels),
+                Operand::LongAddress(addr) => format!("long {}", address(*addr, labels)),
+                Operand::Keyword(keyword) => String::from(*keyword),
+            })
+            .collect();
+
+        if operands.is_empty() {
+            String::from(self.mnemonic)
+        } else {
+            format!("{} {}", self.mnemonic, operands.join(", "))
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(&BTreeMap::new()))
+    }
+}
+
+fn address(addr: u16, labels: &BTreeMap<u16, String>) -> String {
+    match labels.get(&addr) {
+        Some(label) => label.clone(),
+        None => format!("{:#05X}", addr),
+    }
+}
+
+/// Decode the instruction at the start of `bytes`, `None` for data and unknown opcodes.
+/// Opcodes `variant` doesn't have are unknown, as they are to the interpreter
+pub fn decode(bytes: &[u8], variant: Variant) -> Option<Op> {
+    if bytes.len() < 2 {
+        return None;
+    }
+
+    use Operand::*;
+    let opcode = ((bytes[0] as u16) << 8) | bytes[1] as u16;
+    if !variant.supports(opcode) {
+        return None;
+    }
+    let x = ((opcode & 0x0F00) >> 8) as u8;
+    let y = ((opcode & 0x00F0) >> 4) as u8;
+    let n = (opcode & 0x000F) as u8;
+    let kk = (opcode & 0x00FF) as u8;
+    let nnn = opcode & 0x0FFF;
+
+    let op = |mnemonic: &'static str, operands: Vec<Operand>, flow: Flow| {
+        Some(Op {
+            opcode,
+            long: None,
+            mnemonic,
+            operands,
+            flow,
+        })
+    };
+
+    match opcode >> 12 {
+        0x0 => match opcode {
+            0x00E0 => op("CLS", vec![], Flow::Next),
+            0x00EE => op("RET", vec![], Flow::Stop),
+            0x00C0..=0x00CF => op("SCD", vec![Nibble(n)], Flow::Next),
+            0x00FB => op("SCR", vec![], Flow::Next),
+            0x00FC => op("SCL", vec![], Flow::Next),
+            0x00FD => op("EXIT", vec![], Flow::Stop),
+            0x00FE => op("LOW", vec![], Flow::Next),
+            0x00FF => op("HIGH", vec![], Flow::Next),
+            _ => None,
+        },
+        0x1 => op("JMP", vec![Address(nnn)], Flow::Jump(nnn)),
+        0x2 => op("CALL", vec![Address(nnn)], Flow::Call(nnn)),
+        0x3 => op("SE", vec![Register(x), Byte(kk)], Flow::Skip),
+        0x4 => op("SNE", vec![Register(x), Byte(kk)], Flow::Skip),
+        0x5 => match n {
+            0x0 => op("SE", vec![Register(x), Register(y)], Flow::Skip),
+            0x2 => op("SAVE", vec![Range(x, y)], Flow::Next),
+            0x3 => op("LOAD", vec![Range(x, y)], Flow::Next),
+            _ => None,
+        },
+        0x6 => op("LD", vec![Register(x), Byte(kk)], Flow::Next),
+        0x7 => op("ADD", vec![Register(x), Byte(kk)], Flow::Next),
+        0x8 => {
+            let mnemonic = match n {
+                0x0 => "LD",
+                0x1 => "OR",
+                0x2 => "AND",
+                0x3 => "XOR",
+                0x4 => "ADD",
+                0x5 => "SUB",
+                0x6 => "SHR",
+                0x7 => "SUBN",
+                0xE => "SHL",
+                _ => return None,
+            };
+            op(mnemonic, vec![Register(x), Register(y)], Flow::Next)
+        }
+        0x9 if n == 0 => op("SNE", vec![Register(x), Register(y)], Flow::Skip),
+        0xA => op("LD", vec![Keyword("I"), Address(nnn)], Flow::Next),
+        0xB => op("JP", vec![Keyword("V0"), Address(nnn)], Flow::Stop),
+        0xC => op("RND", vec![Register(x), Byte(kk)], Flow::Next),
+        0xD => op("DRW", vec![Register(x), Register(y), Nibble(n)], Flow::Next),
+        0xE => match kk {
+            0x9E => op("SKP", vec![Register(x)], Flow::Skip),
+            0xA1 => op("SKNP", vec![Register(x)], Flow::Skip),
+            _ => None,
+        },
+        0xF => match kk {
+            0x00 if x == 0 => {
+                if bytes.len() < 4 {
+                    return None;
+                }
+                let addr = ((bytes[2] as u16) << 8) | bytes[3] as u16;
+                Some(Op {
+                    opcode,
+                    long: Some(addr),
+                    mnemonic: "LD",
+                    operands: vec![Keyword("I"), LongAddress(addr)],
+                    flow: Flow::Next,
+                })
+            }
+            0x01 => op("PLANE", vec![Nibble(x)], Flow::Next),
+            0x02 if x == 0 => op("AUDIO", vec![], Flow::Next),
+            0x07 => op("LD", vec![Register(x), Keyword("DT")], Flow::Next),
+            0x0A => op("LD", vec![Register(x), Keyword("K")], Flow::Next),
+            0x15 => op("LD", vec![Keyword("DT"), Register(x)], Flow::Next),
+            0x18 => op("LD", vec![Keyword("ST"), Register(x)], Flow::Next),
+            0x1E => op("ADD", vec![Keyword("I"), Register(x)], Flow::Next),
+            0x29 => op("LD", vec![Keyword("F"), Register(x)], Flow::Next),
+            0x30 => op("LD", vec![Keyword("HF"), Register(x)], Flow::Next),
+            0x33 => op("LD", vec![Keyword("B"), Register(x)], Flow::Next),
+            0x3A => op("PITCH", vec![Register(x)], Flow::Next),
+            0x55 => op("LD", vec![Keyword("[I]"), Register(x)], Flow::Next),
+            0x65 => op("LD", vec![Register(x), Keyword("[I]")], Flow::Next),
+            0x75 => op("LD", vec![Keyword("R"), Register(x)], Flow::Next),
+            0x85 => op("LD", vec![Register(x), Keyword("R")], Flow::Next),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A rom split into code and data by following control flow from the start address
+#[derive(Debug, Clone)]
+pub struct Listing {
+    /// Instructions reachable from the start address, by address
+    pub code: BTreeMap<u16, Op>,
+    /// Label names by address
+    pub labels: BTreeMap<u16, String>,
+    rom: Vec<u8>,
+}
+
+impl Listing {
+    /// Trace `rom` loaded at the start address, decoding what `variant` runs
+    pub fn new(rom: &[u8], variant: Variant) -> Self {
+        let end = START_ADDRESS as usize + rom.len();
+        let fetch = |addr: u16| -> Option<Op> {
+            let offset = (addr as usize).checked_sub(START_ADDRESS as usize)?;
+            if addr as usize >= end {
+                return None;
+            }
+            decode(&rom[offset..], variant)
+        };
+
+        let mut code = BTreeMap::new();
+        let mut calls = BTreeSet::new();
+        let mut jumps = BTreeSet::new();
+        let mut pending = vec![START_ADDRESS];
+
+        while let Some(addr) = pending.pop() {
+            if code.contains_key(&addr) {
+                continue;
+            }
+            let op = match fetch(addr) {
+                Some(op) => op,
+                None => continue,
+            };
+
+            let next = addr.wrapping_add(op.len() as u16);
+            match op.flow {
+                Flow::Next => pending.push(next),
+                Flow::Skip => {
+                    pending.push(next);
+                    // `F000 nnnn` is skipped as a whole, it only decodes on XO-CHIP
+                    let skipped = fetch(next).map_or(2, |skipped| skipped.len() as u16);
+                    pending.push(next.wrapping_add(skipped));
+                }
+                Flow::Jump(target) => {
+                    jumps.insert(target);
+                    pending.push(target);
+                }
+                Flow::Call(target) => {
+                    calls.insert(target);
+                    pending.push(target);
+                    pending.push(next);
+                }
+                Flow::Stop => {}
+            }
+
+            code.insert(addr, op);
+        }
+
+        // an instruction overlapping the start of another one can't be listed as code
+        let starts: Vec<u16> = code.keys().copied().collect();
+        for pair in starts.windows(2) {
+            if (pair[0] as usize + code[&pair[0]].len()) > pair[1] as usize {
+                code.remove(&pair[0]);
+            }
+        }
+
+        let mut labels = BTreeMap::new();
+        for op in code.values() {
+            if let (Some(target), Flow::Next) = (op.target(), op.flow) {
+                labels.insert(target, format!("data_{:03X}", target));
+            }
+        }
+        for target in jumps {
+            labels.insert(target, format!("label_{:03X}", target));
+        }
+        for target in calls {
+            labels.insert(target, format!("sub_{:03X}", target));
+        }
+
+        // labels need a line of their own inside the rom
+        let mut listing = Listing {
+            code,
+            labels,
+            rom: rom.to_vec(),
+        };
+        let inside: Vec<u16> = listing
+            .labels
+            .keys()
+            .copied()
+            .filter(|addr| !listing.is_inside_instruction(*addr))
+            .collect();
+        listing.labels.retain(|addr, _| {
+            inside.contains(addr) && *addr >= START_ADDRESS && (*addr as usize) < end
+        });
+
+        listing
+    }
+
+    fn is_inside_instruction(&self, addr: u16) -> bool {
+        self.code
+            .range(..addr)
+            .next_back()
+            .is_some_and(|(start, op)| (*start as usize + op.len()) > addr as usize)
+    }
+
+    /// Render as assembly, one instruction or `db` line per line with its address in a comment
+    pub fn render(&self) -> String {
+        let mut lines = Vec::new();
+        let end = START_ADDRESS as usize + self.rom.len();
+        let mut addr = START_ADDRESS as usize;
+
+        while addr < end {
+            if let Some(label) = self.labels.get(&(addr as u16)) {
+                lines.push(format!("{}:", label));
+            }
+
+            if let Some(op) = self.code.get(&(addr as u16)) {
+                let bytes = match op.long {
+                    Some(long) => format!("{:04X} {:04X}", op.opcode, long),
+                    None => format!("{:04X}", op.opcode),
+                };
+                lines.push(format!("    {:<23} ; {:#05X}  {}", op.render(&self.labels), addr, bytes));
+                addr += op.len();
+                continue;
+            }
+
+            // data runs up to the next instruction, label or line break
+            let mut data_end = addr + 1;
+            while data_end < end
+                && data_end - addr < DATA_PER_LINE
+                && !self.code.contains_key(&(data_end as u16))
+                && !self.labels.contains_key(&(data_end as u16))
+            {
+                data_end += 1;
+            }
+
+            let offset = addr - START_ADDRESS as usize;
+            let bytes: Vec<String> = self.rom[offset..offset + data_end - addr]
+                .iter()
+                .map(|byte| format!("{:#04X}", byte))
+                .collect();
+            lines.push(format!("    {:<23} ; {:#05X}", format!("db {}", bytes.join(", ")), addr));
+            addr = data_end;
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Disassemble a rom to assembly text, see `Listing`
+pub fn disassemble(rom: &[u8], variant: Variant) -> String {
+    Listing::new(rom, variant).render()
+}
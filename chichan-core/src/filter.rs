@@ -0,0 +1,234 @@
+// Display filters: the framebuffer as RGBA, optionally smoothing the flicker of XOR drawn sprites
+use crate::chip8::Chip8;
+use crate::framebuffer::Framebuffer;
+use crate::frontend::Frontend;
+use crate::palette::{Palette, Rgb};
+use std::collections::VecDeque;
+use std::iter;
+use std::str::FromStr;
+
+/// Light a `phosphor` pixel keeps each frame when no decay is given
+pub const DEFAULT_DECAY: f32 = 0.6;
+/// Frames a `phosphor` pixel shows in when no count is given, the one it's lit in included
+pub const DEFAULT_FRAMES: usize = 8;
+/// Most frames `phosphor` blends, a second's worth
+pub const MAX_FRAMES: usize = 60;
+
+/// How a frame of the display is turned into a picture.
+/// Games erase a sprite and draw it again somewhere else, so a moving sprite is missing
+/// from every other frame: both filters hide that at the cost of some ghosting
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Filter {
+    /// Each frame as the machine drew it
+    #[default]
+    Off,
+    /// A pixel lit in this frame or the one before shows in the color it was last lit in
+    Merge,
+    /// The last `frames` frames blended: a pixel shows in the color it was last lit in,
+    /// weighted `decay` (0 to 1) for every frame since against the background.
+    /// A `frames` of 0 blends the current frame only, like 1
+    Phosphor { decay: f32, frames: usize },
+}
+
+impl Filter {
+    // frames before the current one `render` needs to keep
+    fn history(&self) -> usize {
+        match self {
+            Filter::Off => 0,
+            Filter::Merge => 1,
+            Filter::Phosphor { frames, .. } => frames.saturating_sub(1),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    /// `off`, `merge`, `phosphor`, `phosphor:<decay>` or `phosphor:<decay>:<frames>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        match parts[..] {
+            ["off"] => Ok(Filter::Off),
+            ["merge"] => Ok(Filter::Merge),
+            ["phosphor"] => Ok(Filter::Phosphor {
+                decay: DEFAULT_DECAY,
+                frames: DEFAULT_FRAMES,
+            }),
+            ["phosphor", decay] => Ok(Filter::Phosphor {
+                decay: parse_decay(decay)?,
+                frames: DEFAULT_FRAMES,
+            }),
+            ["phosphor", decay, frames] => Ok(Filter::Phosphor {
+                decay: parse_decay(decay)?,
+                frames: parse_frames(frames)?,
+            }),
+            _ => Err(format!(
+                "unknown filter '{}' (expected off, merge, phosphor, phosphor:<decay> or phosphor:<decay>:<frames>)",
+                s
+            )),
+        }
+    }
+}
+
+fn parse_decay(decay: &str) -> Result<f32, String> {
+    match decay.parse::<f32>() {
+        Ok(value) if (0.0..1.0).contains(&value) => Ok(value),
+        _ => Err(format!("phosphor decay '{}' must be at least 0 and less than 1", decay)),
+    }
+}
+
+fn parse_frames(frames: &str) -> Result<usize, String> {
+    match frames.parse::<usize>() {
+        Ok(value) if (1..=MAX_FRAMES).contains(&value) => Ok(value),
+        _ => Err(format!("phosphor frames '{}' must be between 1 and {}", frames, MAX_FRAMES)),
+    }
+}
+
+/// Paints the display as RGBA a frame at a time, through a `Filter`.
+/// It's a `Frontend` too, so a headless run can hand it every frame and the test reads `rgba`
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    filter: Filter,
+    palette: Palette,
+    rgba: Vec<u8>,
+    size: (usize, usize),
+    // the frames before this one the filter blends in, newest first
+    history: VecDeque<Framebuffer>,
+    // the next frame paints every pixel, not just the dirty rows
+    repaint: bool,
+}
+
+impl Renderer {
+    pub fn new(filter: Filter, palette: Palette) -> Self {
+        Renderer {
+            filter,
+            palette,
+            rgba: Vec::new(),
+            size: (0, 0),
+            history: VecDeque::new(),
+            repaint: true,
+        }
+    }
+
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Switch filters, the picture starts over from the next frame
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.history.clear();
+        self.repaint = true;
+    }
+
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// Switch colors, the whole picture is repainted on the next frame, ghosts included
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        self.repaint = true;
+    }
+
+    /// Width and height of `rgba` in pixels
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// The picture, 4 bytes per pixel row after row, empty before the first `render`
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Paint the next frame of `video`, true when the picture changed.
+    /// Call it once per 60 Hz frame, the filters count time in frames.
+    /// Without a filter only the rows `video` reports dirty are painted again,
+    /// with one every pixel is blended from up to `frames` kept frames on each call
+    pub fn render(&mut self, video: &Framebuffer) -> bool {
+        let size = (video.width(), video.height());
+        if size != self.size {
+            self.size = size;
+            self.rgba = vec![0; size.0 * size.1 * 4];
+            self.history.clear();
+            self.repaint = true;
+        }
+
+        let colors = self.palette.colors();
+        let width = size.0;
+        let mut changed = false;
+
+        match self.filter {
+            Filter::Off => {
+                for y in 0..size.1 {
+                    if !self.repaint && !video.is_row_dirty(y) {
+                        continue;
+                    }
+                    let row = &mut self.rgba[y * width * 4..(y + 1) * width * 4];
+                    for (pixel, color) in row.chunks_exact_mut(4).zip(video.row(y)) {
+                        changed |= paint(pixel, colors[color]);
+                    }
+                }
+            }
+            Filter::Merge | Filter::Phosphor { .. } => {
+                let decay = match self.filter {
+                    Filter::Phosphor { decay, .. } => decay,
+                    _ => 1.0,
+                };
+                for y in 0..size.1 {
+                    for x in 0..width {
+                        // the newest frame the pixel is lit in and how old it is, never a mix of two frames' colors
+                        let lit = iter::once(video)
+                            .chain(self.history.iter())
+                            .enumerate()
+                            .find_map(|(age, frame)| match frame.get(x, y) {
+                                0 => None,
+                                color => Some((age, color)),
+                            });
+                        let rgb = match lit {
+                            Some((age, color)) => mix(colors[0], colors[color], decay.powi(age as i32)),
+                            None => colors[0],
+                        };
+                        let at = (y * width + x) * 4;
+                        changed |= paint(&mut self.rgba[at..at + 4], rgb);
+                    }
+                }
+            }
+        }
+
+        if self.filter.history() > 0 {
+            self.history.push_front(video.clone());
+            self.history.truncate(self.filter.history());
+        }
+        self.repaint = false;
+        changed
+    }
+}
+
+/// Nothing to do with the keys or the sound, `draw` renders the frame
+impl<R> Frontend<R> for Renderer {
+    fn read_keys(&mut self, _keypad: &mut [u8; 16]) {}
+
+    fn draw(&mut self, chip: &Chip8<R>) {
+        self.render(&chip.video);
+    }
+
+    fn set_sound(&mut self, _chip: &Chip8<R>, _beeping: bool) {}
+}
+
+// set an RGBA pixel, true if it changed
+fn paint(pixel: &mut [u8], [r, g, b]: Rgb) -> bool {
+    let rgba = [r, g, b, 0xFF];
+    let changed = pixel != rgba;
+    pixel.copy_from_slice(&rgba);
+    changed
+}
+
+// `weight` of the way from `background` to `color`, rounded towards `background` so a fading pixel gets there
+fn mix(background: Rgb, color: Rgb, weight: f32) -> Rgb {
+    let channel = |i: usize| {
+        let kept = ((color[i] as i32 - background[i] as i32) as f32 * weight) as i32;
+        (background[i] as i32 + kept) as u8
+    };
+    [channel(0), channel(1), channel(2)]
+}
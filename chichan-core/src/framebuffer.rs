@@ -0,0 +1,289 @@
+// Framebuffer: the display stored one bit per pixel, a word per row
+use crate::palette::Palette;
+use core::fmt;
+
+/// Size of the CHIP-8 screen
+pub const WIDTH: usize = 64;
+pub const HEIGHT: usize = 32;
+/// Size of the SUPER-CHIP hires screen
+pub const HIRES_WIDTH: usize = 128;
+pub const HIRES_HEIGHT: usize = 64;
+/// Bitplanes, XO-CHIP draws to two, everything else only to the first
+pub const PLANES: usize = 2;
+
+type Plane = [u128; HIRES_HEIGHT];
+
+/// The display of a `Chip8`, one bit per pixel in each plane.
+/// A row is a `u128` read from the most significant bit, pixel x is bit `127 - x`,
+/// so a sprite byte lines up with it by a shift. Lores uses the top 64 bits of the first 32 rows
+#[derive(Clone)]
+pub struct Framebuffer {
+    planes: [Plane; PLANES],
+    hires: bool,
+    // bit y set when row y changed since `clear_dirty`
+    dirty: u64,
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Same mode and pixels, whatever was drawn since the last `clear_dirty`
+impl PartialEq for Framebuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.hires == other.hires && self.planes == other.planes
+    }
+}
+
+impl Eq for Framebuffer {}
+
+impl fmt::Debug for Framebuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Framebuffer {}x{}", self.width(), self.height())?;
+        for row in self.rows() {
+            f.write_str("\n")?;
+            for color in row {
+                write!(f, "{}", color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Framebuffer {
+    /// A blank lores screen, every row dirty so the first draw shows it
+    pub fn new() -> Self {
+        Framebuffer {
+            planes: [[0; HIRES_HEIGHT]; PLANES],
+            hires: false,
+            dirty: !0,
+        }
+    }
+
+    /// Width in pixels of the current mode
+    pub fn width(&self) -> usize {
+        if self.hires {
+            HIRES_WIDTH
+        } else {
+            WIDTH
+        }
+    }
+
+    /// Height in pixels of the current mode
+    pub fn height(&self) -> usize {
+        if self.hires {
+            HIRES_HEIGHT
+        } else {
+            HEIGHT
+        }
+    }
+
+    /// SUPER-CHIP 128x64 mode, 64x32 otherwise
+    pub fn hires(&self) -> bool {
+        self.hires
+    }
+
+    /// Switch between 64x32 and 128x64, both planes are cleared
+    pub fn set_hires(&mut self, hires: bool) {
+        self.hires = hires;
+        self.planes = [[0; HIRES_HEIGHT]; PLANES];
+        self.dirty = !0;
+    }
+
+    /// Color of a pixel, 0 to 3 with bit n set when it's lit in plane n
+    pub fn get(&self, x: usize, y: usize) -> usize {
+        (0..PLANES).fold(0, |color, plane| color | (self.is_lit(plane, x, y) as usize) << plane)
+    }
+
+    pub fn is_lit(&self, plane: usize, x: usize, y: usize) -> bool {
+        self.row_bits(plane, y) & bit(x) != 0
+    }
+
+    pub fn set(&mut self, plane: usize, x: usize, y: usize, lit: bool) {
+        let row = self.row_bits(plane, y);
+        self.set_row_bits(plane, y, if lit { row | bit(x) } else { row & !bit(x) });
+    }
+
+    /// Pixels of one row of a plane, see `Framebuffer` for the layout
+    pub fn row_bits(&self, plane: usize, y: usize) -> u128 {
+        assert!(y < self.height(), "row {} is off screen", y);
+        self.planes[plane][y]
+    }
+
+    /// Replace a row of a plane, bits past the right edge are dropped
+    pub fn set_row_bits(&mut self, plane: usize, y: usize, bits: u128) {
+        assert!(y < self.height(), "row {} is off screen", y);
+        self.planes[plane][y] = bits & self.row_mask();
+        self.dirty |= 1 << y;
+    }
+
+    /// XOR `bits` into a row of a plane, true when a lit pixel was turned off
+    pub fn xor_row(&mut self, plane: usize, y: usize, bits: u128) -> bool {
+        let row = self.row_bits(plane, y);
+        let bits = bits & self.row_mask();
+        if bits != 0 {
+            self.planes[plane][y] = row ^ bits;
+            self.dirty |= 1 << y;
+        }
+
+        row & bits != 0
+    }
+
+    /// Clear the planes selected by the `planes` bitmask, as in `Chip8::planes`
+    pub fn clear(&mut self, planes: u8) {
+        self.each_row(planes, |_| 0);
+    }
+
+    /// Move the selected planes down by `n` rows, blank rows come in at the top
+    pub fn scroll_down(&mut self, planes: u8, n: usize) {
+        let height = self.height();
+        for plane in selected(planes) {
+            let rows = &mut self.planes[plane][..height];
+            if n < height {
+                rows.copy_within(..height - n, n);
+            }
+            rows[..n.min(height)].fill(0);
+        }
+        self.dirty = !0;
+    }
+
+    /// Move the selected planes right by `n` pixels
+    pub fn scroll_right(&mut self, planes: u8, n: usize) {
+        self.each_row(planes, |row| row.checked_shr(n as u32).unwrap_or(0));
+    }
+
+    /// Move the selected planes left by `n` pixels
+    pub fn scroll_left(&mut self, planes: u8, n: usize) {
+        self.each_row(planes, |row| row.checked_shl(n as u32).unwrap_or(0));
+    }
+
+    /// Colors of the pixels of row `y`, left to right
+    pub fn row(&self, y: usize) -> Row<'_> {
+        assert!(y < self.height(), "row {} is off screen", y);
+        Row {
+            framebuffer: self,
+            y,
+            x: 0,
+        }
+    }
+
+    /// Every row of the current mode, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        (0..self.height()).map(move |y| self.row(y))
+    }
+
+    /// Write the screen as RGBA in `palette`'s colors, 4 bytes per pixel row after row.
+    /// `out` must be `width * height * 4` bytes long
+    pub fn write_rgba(&self, palette: &Palette, out: &mut [u8]) {
+        assert_eq!(out.len(), self.width() * self.height() * 4, "RGBA buffer has the wrong size");
+
+        let palette = palette.colors();
+        let colors = self.rows().flatten();
+        for (pixel, color) in out.chunks_exact_mut(4).zip(colors) {
+            let [r, g, b] = palette[color];
+            pixel.copy_from_slice(&[r, g, b, 0xFF]);
+        }
+    }
+
+    /// `write_rgba` into a new buffer
+    #[cfg(feature = "std")]
+    pub fn to_rgba(&self, palette: &Palette) -> Vec<u8> {
+        let mut rgba = vec![0; self.width() * self.height() * 4];
+        self.write_rgba(palette, &mut rgba);
+        rgba
+    }
+
+    /// Whether anything changed since `clear_dirty`
+    pub fn is_dirty(&self) -> bool {
+        self.dirty & self.rows_mask() != 0
+    }
+
+    pub fn is_row_dirty(&self, y: usize) -> bool {
+        y < self.height() && self.dirty & (1 << y) != 0
+    }
+
+    /// Rows changed since `clear_dirty`, top to bottom.
+    /// A mode switch or a scroll marks every row
+    pub fn dirty_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.height()).filter(move |y| self.dirty & (1 << y) != 0)
+    }
+
+    /// Forget the changes, called once the display has been redrawn
+    pub fn clear_dirty(&mut self) {
+        self.dirty = 0;
+    }
+
+    /// Mark every row as changed, e.g. after restoring a save state
+    pub fn mark_dirty(&mut self) {
+        self.dirty = !0;
+    }
+
+    fn each_row<F: FnMut(u128) -> u128>(&mut self, planes: u8, mut op: F) {
+        let height = self.height();
+        let mask = self.row_mask();
+        for plane in selected(planes) {
+            for row in self.planes[plane][..height].iter_mut() {
+                *row = op(*row) & mask;
+            }
+        }
+        self.dirty = !0;
+    }
+
+    // pixels of a row that are on screen in the current mode
+    fn row_mask(&self) -> u128 {
+        if self.hires {
+            !0
+        } else {
+            !0 << (128 - WIDTH)
+        }
+    }
+
+    fn rows_mask(&self) -> u64 {
+        if self.hires {
+            !0
+        } else {
+            (1 << HEIGHT) - 1
+        }
+    }
+}
+
+/// Colors of one row of a `Framebuffer`, see `Framebuffer::row`
+#[derive(Debug, Clone)]
+pub struct Row<'a> {
+    framebuffer: &'a Framebuffer,
+    y: usize,
+    x: usize,
+}
+
+impl<'a> Iterator for Row<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.x >= self.framebuffer.width() {
+            return None;
+        }
+        let color = self.framebuffer.get(self.x, self.y);
+        self.x += 1;
+        Some(color)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.framebuffer.width() - self.x;
+        (left, Some(left))
+    }
+}
+
+impl<'a> ExactSizeIterator for Row<'a> {}
+
+/// The bit of pixel `x` in a row
+pub fn bit(x: usize) -> u128 {
+    assert!(x < HIRES_WIDTH, "column {} is off screen", x);
+    1 << (127 - x)
+}
+
+// plane numbers selected by a `Chip8::planes` style bitmask
+fn selected(planes: u8) -> impl Iterator<Item = usize> {
+    (0..PLANES).filter(move |plane| planes & (1 << plane) != 0)
+}
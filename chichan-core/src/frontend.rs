@@ -0,0 +1,45 @@
+// Frontend: what a window, terminal or test harness provides to drive a `Chip8`
+use crate::chip8::{Chip8, Chip8Error, StepEvent};
+use crate::random::{DefaultRandom, Random};
+
+/// Display, input and audio for a machine, called once per 60 Hz frame by `run_frame`.
+/// `R` is the machine's random source, frontends usually don't care
+pub trait Frontend<R = DefaultRandom> {
+    /// Set `keypad` to the keys held down right now, 1 pressed and 0 released
+    fn read_keys(&mut self, keypad: &mut [u8; 16]);
+
+    /// Show the machine's display, see `Chip8::color_index`.
+    /// `chip.video.dirty_rows()` are the rows changed since the last `draw` through `run_frame`
+    fn draw(&mut self, chip: &Chip8<R>);
+
+    /// Start or stop the buzzer, XO-CHIP roms also set `chip.audio_pattern` and `chip.pitch`
+    fn set_sound(&mut self, chip: &Chip8<R>, beeping: bool);
+}
+
+/// No window, no keys, no sound
+impl<R> Frontend<R> for () {
+    fn read_keys(&mut self, _keypad: &mut [u8; 16]) {}
+
+    fn draw(&mut self, _chip: &Chip8<R>) {}
+
+    fn set_sound(&mut self, _chip: &Chip8<R>, _beeping: bool) {}
+}
+
+/// Run one frame with the frontend's keys, then hand it the sound and the display.
+/// A faulted machine is drawn as it was when it stopped and goes quiet.
+/// The display's dirty rows are cleared once it's drawn
+pub fn run_frame<R: Random, F: Frontend<R> + ?Sized>(chip: &mut Chip8<R>, frontend: &mut F) -> Result<StepEvent, Chip8Error> {
+    frontend.read_keys(&mut chip.keypad);
+
+    let result = chip.run_frame();
+    let beeping = match result {
+        Ok(StepEvent::Exited) | Err(_) => false,
+        Ok(_) => chip.is_beeping(),
+    };
+
+    frontend.set_sound(chip, beeping);
+    frontend.draw(chip);
+    chip.video.clear_dirty();
+
+    result
+}
@@ -0,0 +1,135 @@
+// Headless runner: run a rom for a number of frames and report the final machine, no window needed
+use crate::chip8::{Chip8, Chip8Error, StepEvent};
+use crate::frontend::{self, Frontend};
+use std::fmt;
+
+/// Characters the ASCII dump uses for the four colors
+const ASCII_COLORS: [char; 4] = ['.', '#', '+', '@'];
+
+/// How a headless run ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// Every requested frame ran
+    Finished,
+    /// The rom quit with `00FD`
+    Exited,
+    /// The rom faulted, the machine can't go on
+    Halted(Chip8Error),
+}
+
+impl Outcome {
+    /// Process exit status, 0 unless the machine halted
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Outcome::Finished | Outcome::Exited => 0,
+            Outcome::Halted(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Finished => write!(f, "finished"),
+            Outcome::Exited => write!(f, "exited"),
+            Outcome::Halted(err) => write!(f, "halted: {}", err),
+        }
+    }
+}
+
+/// Keypad input played back by frame number, for scripted runs and tests
+#[derive(Debug, Clone, Default)]
+pub struct KeyScript {
+    /// (frame, key, pressed), applied at the start of that frame
+    pub events: Vec<(u32, usize, bool)>,
+    frame: u32,
+    keypad: [u8; 16],
+}
+
+impl KeyScript {
+    pub fn new(events: &[(u32, usize, bool)]) -> Self {
+        KeyScript {
+            events: events.to_vec(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<R> Frontend<R> for KeyScript {
+    fn read_keys(&mut self, keypad: &mut [u8; 16]) {
+        let now = self.frame;
+        for (_, key, pressed) in self.events.iter().filter(|(frame, _, _)| *frame == now) {
+            self.keypad[*key] = *pressed as u8;
+        }
+        self.frame += 1;
+        *keypad = self.keypad;
+    }
+
+    fn draw(&mut self, _chip: &Chip8<R>) {}
+
+    fn set_sound(&mut self, _chip: &Chip8<R>, _beeping: bool) {}
+}
+
+/// Run up to `frames` 60 Hz frames as fast as possible.
+/// Returns the frames run and why the run ended
+pub fn run_frames(chip: &mut Chip8, frames: u32) -> (u32, Outcome) {
+    run_frames_with(chip, &mut (), frames)
+}
+
+/// `run_frames` with a frontend providing the keys and taking the display and sound
+pub fn run_frames_with<F: Frontend + ?Sized>(chip: &mut Chip8, frontend: &mut F, frames: u32) -> (u32, Outcome) {
+    for frame in 0..frames {
+        match frontend::run_frame(chip, frontend) {
+            Ok(StepEvent::Exited) => return (frame + 1, Outcome::Exited),
+            Ok(_) => {}
+            Err(err) => return (frame + 1, Outcome::Halted(err)),
+        }
+    }
+
+    (frames, Outcome::Finished)
+}
+
+/// The display, one character per pixel: `.` off, `#` on, `+` and `@` for the XO-CHIP colors 2 and 3
+pub fn screen_ascii(chip: &Chip8) -> String {
+    let lines: Vec<String> = (0..chip.height())
+        .map(|y| (0..chip.width()).map(|x| ASCII_COLORS[chip.color_index(x, y)]).collect())
+        .collect();
+
+    lines.join("\n")
+}
+
+/// Registers, timers and the stack
+pub fn registers(chip: &Chip8) -> String {
+    let registers: Vec<String> = chip
+        .registers
+        .iter()
+        .enumerate()
+        .map(|(x, value)| format!("V{:X}={:02X}", x, value))
+        .collect();
+    let stack: Vec<String> = chip.stack[..chip.sp as usize]
+        .iter()
+        .map(|addr| format!("{:#05X}", addr))
+        .collect();
+
+    format!(
+        "{}\n{}\nI={:#05X} PC={:#05X} DT={} ST={}\nstack: [{}]",
+        registers[..8].join(" "),
+        registers[8..].join(" "),
+        chip.index,
+        chip.pc,
+        chip.delay_timer,
+        chip.sound_timer,
+        stack.join(", ")
+    )
+}
+
+/// What `chichan run --headless --frames N` prints when it's done
+pub fn report(chip: &Chip8, frames: u32, outcome: Outcome) -> String {
+    format!(
+        "{}\n\n{}\nframes: {}\nstatus: {}",
+        screen_ascii(chip),
+        registers(chip),
+        frames,
+        outcome
+    )
+}
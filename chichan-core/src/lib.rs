@@ -0,0 +1,75 @@
+//! CHIP-8, SUPER-CHIP and XO-CHIP emulator core: the machine, its tooling and the `Frontend`
+//! trait a window or test harness implements to drive it. No graphics or audio dependencies,
+//! `rand` is behind the default `rand` feature.
+//!
+//! Without the default `std` feature the crate is `no_std` and allocates nothing: only the machine,
+//! its quirks and the frontend trait are left, roms are loaded with `Chip8::load_rom_bytes`
+//! and `Chip8::with_rng` injects the random source. A `Chip8` is about 71 KiB whatever the variant,
+//! the target needs that much RAM for it
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+pub mod asm;
+#[cfg(feature = "std")]
+pub mod audio;
+pub mod chip8;
+#[cfg(feature = "std")]
+pub mod debugger;
+#[cfg(feature = "std")]
+pub mod disasm;
+#[cfg(feature = "std")]
+pub mod filter;
+pub mod framebuffer;
+pub mod frontend;
+#[cfg(feature = "std")]
+pub mod headless;
+pub mod palette;
+pub mod quirks;
+pub mod random;
+#[cfg(feature = "std")]
+pub mod rewind;
+#[cfg(feature = "std")]
+pub mod savestate;
+
+pub use chip8::Chip8;
+pub use framebuffer::Framebuffer;
+pub use frontend::Frontend;
+pub use palette::Palette;
+pub use random::Random;
+
+#[allow(clippy::module_inception)]
+mod test_asm;
+#[allow(clippy::module_inception)]
+mod test_audio;
+#[allow(clippy::module_inception)]
+mod test_chip8;
+#[allow(clippy::module_inception)]
+mod test_debugger;
+#[allow(clippy::module_inception)]
+mod test_disasm;
+#[allow(clippy::module_inception)]
+mod test_draw;
+#[allow(clippy::module_inception)]
+mod test_filter;
+#[allow(clippy::module_inception)]
+mod test_framebuffer;
+#[allow(clippy::module_inception)]
+mod test_frontend;
+#[allow(clippy::module_inception)]
+mod test_golden;
+#[allow(clippy::module_inception)]
+mod test_headless;
+#[allow(clippy::module_inception)]
+mod test_palette;
+#[allow(clippy::module_inception)]
+mod test_quirks;
+#[allow(clippy::module_inception)]
+mod test_random;
+#[allow(clippy::module_inception)]
+mod test_rewind;
+#[allow(clippy::module_inception)]
+mod test_savestate;
+#[allow(clippy::module_inception)]
+mod test_schip;
+#[allow(clippy::module_inception)]
+mod test_xochip;
@@ -0,0 +1,125 @@
+// Display colors, shared by the window and the headless dumps
+#[cfg(feature = "std")]
+use std::str::FromStr;
+
+/// Red, green and blue
+pub type Rgb = [u8; 3];
+
+/// What the four pixel colors of `Chip8::color_index` look like on screen.
+/// `Palette::default()` is the classic black and white
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// Unlit pixels, the window's border uses it too
+    pub background: Rgb,
+    /// Lit pixels, for XO-CHIP the ones lit in the first plane only
+    pub foreground: Rgb,
+    /// XO-CHIP pixels lit in the second plane only
+    pub plane2: Rgb,
+    /// XO-CHIP pixels lit in both planes
+    pub both: Rgb,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::CLASSIC
+    }
+}
+
+impl Palette {
+    /// White on black, orange and brown for the XO-CHIP planes
+    pub const CLASSIC: Palette = Palette {
+        background: [0, 0, 0],
+        foreground: [255, 255, 255],
+        plane2: [255, 102, 0],
+        both: [102, 33, 0],
+    };
+
+    /// Amber monochrome monitor
+    pub const AMBER: Palette = Palette {
+        background: [26, 13, 0],
+        foreground: [255, 176, 0],
+        plane2: [178, 89, 0],
+        both: [255, 224, 153],
+    };
+
+    /// Green phosphor terminal
+    pub const GREEN_PHOSPHOR: Palette = Palette {
+        background: [0, 20, 0],
+        foreground: [51, 255, 51],
+        plane2: [0, 140, 50],
+        both: [180, 255, 180],
+    };
+
+    /// Four shades of a handheld's greenish LCD, dark pixels on a light screen
+    pub const LCD: Palette = Palette {
+        background: [155, 188, 15],
+        foreground: [15, 56, 15],
+        plane2: [100, 144, 32],
+        both: [48, 98, 48],
+    };
+
+    /// Pure colors as far apart as possible
+    pub const HIGH_CONTRAST: Palette = Palette {
+        background: [0, 0, 0],
+        foreground: [255, 255, 255],
+        plane2: [255, 255, 0],
+        both: [0, 255, 255],
+    };
+
+    /// Okabe-Ito colors, told apart with any kind of color blindness
+    pub const COLORBLIND: Palette = Palette {
+        background: [0, 0, 0],
+        foreground: [255, 255, 255],
+        plane2: [230, 159, 0],
+        both: [86, 180, 233],
+    };
+
+    /// The presets by the name `--palette` takes, in the order the window's hotkey cycles through them
+    pub const PRESETS: [(&'static str, Palette); 6] = [
+        ("classic", Palette::CLASSIC),
+        ("amber", Palette::AMBER),
+        ("green", Palette::GREEN_PHOSPHOR),
+        ("lcd", Palette::LCD),
+        ("contrast", Palette::HIGH_CONTRAST),
+        ("colorblind", Palette::COLORBLIND),
+    ];
+
+    /// The colors indexed by `Chip8::color_index`
+    pub fn colors(&self) -> [Rgb; 4] {
+        [self.background, self.foreground, self.plane2, self.both]
+    }
+
+    /// Name of the preset this palette is, `None` for custom colors
+    pub fn name(&self) -> Option<&'static str> {
+        Self::PRESETS
+            .iter()
+            .find(|(_, preset)| preset == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// The preset after this one, custom colors go back to the first preset
+    pub fn next(&self) -> Palette {
+        let position = Self::PRESETS.iter().position(|(_, preset)| preset == self);
+        match position {
+            Some(position) => Self::PRESETS[(position + 1) % Self::PRESETS.len()].1,
+            None => Self::PRESETS[0].1,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Palette {
+    type Err = String;
+
+    /// Look up a preset by its name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::PRESETS
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, preset)| *preset)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::PRESETS.iter().map(|(name, _)| *name).collect();
+                format!("unknown palette '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
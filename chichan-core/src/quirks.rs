@@ -0,0 +1,106 @@
+// Behaviour differences between CHIP-8 interpreters
+#[cfg(feature = "std")]
+use std::str::FromStr;
+
+/// Toggles for the opcodes that CHIP-8 interpreters disagree on.
+/// `Quirks::default()` keeps chichan's original behaviour
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Quirks {
+    /// `8xy6`/`8xyE` shift Vy into Vx instead of shifting Vx in place
+    pub shift_uses_vy: bool,
+    /// Where `Fx55`/`Fx65` leave I after storing or loading the registers
+    pub load_store_index: IndexIncrement,
+    /// `Bnnn` jumps to nnn + Vx (x being the high nibble of nnn) instead of nnn + V0
+    pub jump_uses_vx: bool,
+    /// `8xy1`/`8xy2`/`8xy3` reset VF to 0
+    pub vf_reset: bool,
+    /// `Dxyn` waits for the next frame, so at most one sprite is drawn per frame
+    pub display_wait: bool,
+    /// What happens to the part of a sprite crossing the right or bottom edge
+    pub edge_mode: EdgeMode,
+}
+
+/// How `Dxyn` draws the pixels of a sprite that fall past the right or bottom edge.
+/// The sprite's top left corner always wraps around, only the rest of it is affected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMode {
+    /// Pixels past the edge wrap around to the opposite side of the screen, on the same row or column
+    #[default]
+    Wrap,
+    /// Pixels past the edge are dropped and can't collide
+    Clip,
+}
+
+/// How far `Fx55`/`Fx65` move I once the registers are stored or loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexIncrement {
+    /// I is left where it was
+    #[default]
+    Unchanged,
+    /// I is moved past the last register, by x + 1
+    PastLast,
+    /// I is moved by x, onto the last register
+    ByX,
+}
+
+impl Quirks {
+    /// The original COSMAC VIP interpreter
+    pub const COSMAC_VIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        load_store_index: IndexIncrement::PastLast,
+        jump_uses_vx: false,
+        vf_reset: true,
+        display_wait: true,
+        edge_mode: EdgeMode::Clip,
+    };
+
+    /// CHIP-48 on the HP-48 calculators, whose `Fx55`/`Fx65` stop one short of
+    /// the VIP's I. SUPER-CHIP 1.1 later dropped the increment altogether
+    pub const CHIP_48: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_index: IndexIncrement::ByX,
+        jump_uses_vx: true,
+        vf_reset: false,
+        display_wait: false,
+        edge_mode: EdgeMode::Clip,
+    };
+
+    /// SUPER-CHIP 1.1
+    pub const SUPER_CHIP: Quirks = Quirks {
+        shift_uses_vy: false,
+        load_store_index: IndexIncrement::Unchanged,
+        jump_uses_vx: true,
+        vf_reset: false,
+        display_wait: false,
+        edge_mode: EdgeMode::Clip,
+    };
+
+    /// XO-CHIP as implemented by Octo
+    pub const XO_CHIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        load_store_index: IndexIncrement::PastLast,
+        jump_uses_vx: false,
+        vf_reset: false,
+        display_wait: false,
+        edge_mode: EdgeMode::Wrap,
+    };
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Quirks {
+    type Err = String;
+
+    /// Look up a preset by its profile name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vip" => Ok(Quirks::COSMAC_VIP),
+            "chip48" => Ok(Quirks::CHIP_48),
+            "schip" => Ok(Quirks::SUPER_CHIP),
+            "xochip" => Ok(Quirks::XO_CHIP),
+            _ => Err(format!(
+                "unknown quirks profile '{}' (expected vip, chip48, schip or xochip)",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Random bytes for `Cxkk`, injected into the machine so it runs without an OS
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+/// Source of the random bytes `Cxkk` masks
+pub trait Random {
+    fn next_byte(&mut self) -> u8;
+}
+
+/// Any `FnMut() -> u8` is a source, e.g. a hardware RNG register read
+impl<F: FnMut() -> u8> Random for F {
+    fn next_byte(&mut self) -> u8 {
+        self()
+    }
+}
+
+/// Small seedable xorshift generator, what `Chip8::new` draws from.
+/// Its whole state is one `u32`, so save states can carry it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XorShift {
+    state: u32,
+}
+
+impl XorShift {
+    /// A zero seed would get stuck at zero, so it's replaced by `0x2545F491`:
+    /// seeds 0 and `0x2545F491` draw the same numbers.
+    /// `XorShift::new(rng.state())` continues exactly where `rng` is
+    pub fn new(seed: u32) -> Self {
+        XorShift {
+            state: if seed == 0 { 0x2545_F491 } else { seed },
+        }
+    }
+
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+}
+
+impl Default for XorShift {
+    /// Seeded from the thread's `rand` generator, or the fixed seed without the `rand` feature
+    fn default() -> Self {
+        #[cfg(feature = "rand")]
+        let seed = rand::random();
+        #[cfg(not(feature = "rand"))]
+        let seed = 0;
+
+        Self::new(seed)
+    }
+}
+
+impl Random for XorShift {
+    fn next_byte(&mut self) -> u8 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        (x >> 24) as u8
+    }
+}
+
+/// Plays `bytes` back in order and starts over at the end, for tests that need known `Cxkk` results
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scripted<'a> {
+    bytes: &'a [u8],
+    next: usize,
+}
+
+impl<'a> Scripted<'a> {
+    /// `bytes` must not be empty
+    pub fn new(bytes: &'a [u8]) -> Self {
+        assert!(!bytes.is_empty(), "a scripted random source needs at least one byte");
+        Scripted { bytes, next: 0 }
+    }
+}
+
+impl<'a> Random for Scripted<'a> {
+    fn next_byte(&mut self) -> u8 {
+        let byte = self.bytes[self.next];
+        self.next = (self.next + 1) % self.bytes.len();
+        byte
+    }
+}
+
+/// The thread's `rand` generator, different on every run and can't be seeded or saved
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ThreadRandom;
+
+#[cfg(feature = "rand")]
+impl Random for ThreadRandom {
+    fn next_byte(&mut self) -> u8 {
+        rand::thread_rng().gen::<u8>()
+    }
+}
+
+/// What `Chip8::new` draws from
+pub type DefaultRandom = XorShift;
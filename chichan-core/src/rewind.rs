@@ -0,0 +1,198 @@
+// Rewind: step the machine backwards through recently recorded frames
+use crate::chip8::{Chip8, TIMER_HZ};
+use std::collections::VecDeque;
+
+/// Seconds of history kept by default
+pub const DEFAULT_SECONDS: u32 = 10;
+/// Default cap on the memory used by the recorded history
+pub const DEFAULT_BUDGET: usize = 16 * 1024 * 1024;
+
+/// Ring buffer of save states, newest kept whole and the older ones as backward deltas.
+///
+/// Each delta is the XOR of a state with the one recorded after it, run-length encoded,
+/// so the mostly unchanged memory of consecutive frames costs a few bytes.
+/// The oldest frames are dropped once there are more than `max_frames` of them
+/// or the deltas use more than `budget` bytes
+#[derive(Debug, Clone)]
+pub struct Rewind {
+    max_frames: usize,
+    budget: usize,
+    // state of the last recorded frame
+    latest: Option<Vec<u8>>,
+    // oldest first, `deltas[i]` turns frame i + 1 back into frame i
+    deltas: VecDeque<Vec<u8>>,
+    delta_bytes: usize,
+}
+
+impl Rewind {
+    pub fn new(max_frames: usize, budget: usize) -> Self {
+        Rewind {
+            max_frames,
+            budget,
+            latest: None,
+            deltas: VecDeque::new(),
+            delta_bytes: 0,
+        }
+    }
+
+    /// Keep `seconds` of 60 Hz frames within `budget` bytes.
+    /// Any number of seconds is fine, the budget is what bounds a long history
+    pub fn with_seconds(seconds: u32, budget: usize) -> Self {
+        Self::new((seconds as usize).saturating_mul(TIMER_HZ as usize), budget)
+    }
+
+    /// Number of frames the machine can currently be rewound by
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// False when no frames are kept, so there is nothing to rewind to
+    pub fn is_enabled(&self) -> bool {
+        self.max_frames > 0
+    }
+
+    /// Bytes held by the history, the latest full state included
+    pub fn memory_usage(&self) -> usize {
+        self.delta_bytes + self.latest.as_ref().map_or(0, |state| state.len())
+    }
+
+    /// Forget the whole history, e.g. after loading a save state
+    pub fn clear(&mut self) {
+        self.latest = None;
+        self.deltas.clear();
+        self.delta_bytes = 0;
+    }
+
+    /// Record the machine as the newest frame, call once per `run_frame`
+    pub fn record(&mut self, chip: &Chip8) {
+        let state = chip.save_state();
+
+        if let Some(previous) = self.latest.take() {
+            if self.max_frames > 0 {
+                let delta = encode_delta(&state, &previous);
+                self.delta_bytes += delta.len();
+                self.deltas.push_back(delta);
+            }
+        }
+        self.latest = Some(state);
+
+        while self.deltas.len() > self.max_frames || self.delta_bytes > self.budget {
+            match self.deltas.pop_front() {
+                Some(delta) => self.delta_bytes -= delta.len(),
+                None => break,
+            }
+        }
+    }
+
+    /// Put the machine back `frames` recorded frames before the newest one,
+    /// 0 restores the newest frame itself. Stops at the oldest frame still kept.
+    /// Returns the number of frames actually stepped back
+    pub fn rewind(&mut self, chip: &mut Chip8, frames: usize) -> usize {
+        let mut state = match self.latest.take() {
+            Some(state) => state,
+            None => return 0,
+        };
+
+        let mut stepped = 0;
+        while stepped < frames {
+            let delta = match self.deltas.pop_back() {
+                Some(delta) => delta,
+                None => break,
+            };
+            self.delta_bytes -= delta.len();
+            state = apply_delta(&state, &delta);
+            stepped += 1;
+        }
+
+        chip.load_state(&state)
+            .expect("rewind history only holds states saved by this build");
+        self.latest = Some(state);
+
+        stepped
+    }
+}
+
+/// Encode how to turn `newer` back into `older`:
+/// `older length, xor length` then `zero run, literal length, literal bytes` runs, all lengths LEB128
+fn encode_delta(newer: &[u8], older: &[u8]) -> Vec<u8> {
+    let len = newer.len().max(older.len());
+    let xor: Vec<u8> = (0..len)
+        .map(|i| newer.get(i).copied().unwrap_or(0) ^ older.get(i).copied().unwrap_or(0))
+        .collect();
+
+    let mut delta = Vec::new();
+    push_varint(&mut delta, older.len());
+    push_varint(&mut delta, len);
+
+    let mut i = 0;
+    while i < len {
+        let zeros_start = i;
+        while i < len && xor[i] == 0 {
+            i += 1;
+        }
+        let literal_start = i;
+        while i < len && xor[i] != 0 {
+            i += 1;
+        }
+
+        push_varint(&mut delta, literal_start - zeros_start);
+        push_varint(&mut delta, i - literal_start);
+        delta.extend_from_slice(&xor[literal_start..i]);
+    }
+
+    delta
+}
+
+/// Rebuild the older state from `newer` and a delta made by `encode_delta`
+fn apply_delta(newer: &[u8], delta: &[u8]) -> Vec<u8> {
+    let mut pos = 0;
+    let older_len = read_varint(delta, &mut pos);
+    let len = read_varint(delta, &mut pos);
+
+    let mut state = newer.to_vec();
+    state.resize(len, 0);
+
+    let mut i = 0;
+    while pos < delta.len() {
+        i += read_varint(delta, &mut pos);
+        let literal_len = read_varint(delta, &mut pos);
+        for byte in &delta[pos..pos + literal_len] {
+            state[i] ^= byte;
+            i += 1;
+        }
+        pos += literal_len;
+    }
+
+    state.truncate(older_len);
+    state
+}
+
+fn push_varint(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        value |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+        shift += 7;
+    }
+}
@@ -0,0 +1,430 @@
+// Save states: snapshot and restore a whole machine
+use crate::chip8::{Chip8, Variant};
+use crate::framebuffer::{self, Framebuffer};
+use crate::quirks::{EdgeMode, IndexIncrement, Quirks};
+use crate::random::XorShift;
+use std::error::Error;
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"CHST";
+/// Bumped whenever a section changes layout, older states are rejected
+pub const STATE_VERSION: u16 = 2;
+
+// section tags, a state is the header followed by `tag, u32 length, payload` records
+const TAG_VARIANT: &[u8; 4] = b"VARI";
+const TAG_CPU: &[u8; 4] = b"CPU ";
+const TAG_MEMORY: &[u8; 4] = b"MEM ";
+const TAG_KEYPAD: &[u8; 4] = b"KEYS";
+const TAG_VIDEO: &[u8; 4] = b"VIDE";
+const TAG_QUIRKS: &[u8; 4] = b"QURK";
+const TAG_SCHIP: &[u8; 4] = b"SCHP";
+const TAG_XOCHIP: &[u8; 4] = b"XOCH";
+const TAG_SPEED: &[u8; 4] = b"SPED";
+const TAG_RANDOM: &[u8; 4] = b"RAND";
+
+const CPU_LEN: usize = 16 + 2 + 2 + 16 * 2 + 1 + 1 + 1 + 2;
+
+/// Reasons a save state can't be restored
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateError {
+    /// The blob doesn't start with the save state magic
+    BadMagic,
+    /// Written by a newer or older chichan with a different layout
+    UnsupportedVersion(u16),
+    /// The blob ends in the middle of a header or section
+    Truncated,
+    /// A section every state must have is missing
+    MissingSection(String),
+    /// A section has the wrong size or an impossible value
+    BadSection(String),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateError::BadMagic => write!(f, "not a chichan save state"),
+            StateError::UnsupportedVersion(version) => write!(
+                f,
+                "save state version {} is not supported (expected {})",
+                version, STATE_VERSION
+            ),
+            StateError::Truncated => write!(f, "save state is truncated"),
+            StateError::MissingSection(tag) => write!(f, "save state has no '{}' section", tag),
+            StateError::BadSection(tag) => write!(f, "save state section '{}' is corrupt", tag),
+        }
+    }
+}
+
+impl Error for StateError {}
+
+fn tag_name(tag: &[u8; 4]) -> String {
+    String::from_utf8_lossy(tag).trim_end().to_string()
+}
+
+fn push_section(out: &mut Vec<u8>, tag: &[u8; 4], payload: &[u8]) {
+    out.extend_from_slice(tag);
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+}
+
+/// A plane's rows in the current video mode, one bit per pixel, most significant bit first
+fn pack_plane(video: &Framebuffer, plane: usize) -> Vec<u8> {
+    let row_len = video.width() / 8;
+    let mut packed = Vec::with_capacity(row_len * video.height());
+    for y in 0..video.height() {
+        packed.extend_from_slice(&video.row_bits(plane, y).to_be_bytes()[..row_len]);
+    }
+    packed
+}
+
+fn unpack_plane(packed: &[u8], video: &mut Framebuffer, plane: usize) {
+    let row_len = video.width() / 8;
+    for (y, row) in packed.chunks(row_len).enumerate() {
+        let mut bytes = [0; 16];
+        bytes[..row_len].copy_from_slice(row);
+        video.set_row_bits(plane, y, u128::from_be_bytes(bytes));
+    }
+}
+
+/// Bytes `pack_plane` writes for one plane
+fn plane_len(hires: bool) -> usize {
+    if hires {
+        framebuffer::HIRES_WIDTH * framebuffer::HIRES_HEIGHT / 8
+    } else {
+        framebuffer::WIDTH * framebuffer::HEIGHT / 8
+    }
+}
+
+fn variant_id(variant: Variant) -> u8 {
+    match variant {
+        Variant::Chip8 => 0,
+        Variant::SuperChip => 1,
+        Variant::XoChip => 2,
+    }
+}
+
+fn variant_name(variant: Variant) -> &'static str {
+    match variant {
+        Variant::Chip8 => "chip8",
+        Variant::SuperChip => "schip",
+        Variant::XoChip => "xochip",
+    }
+}
+
+fn quirk_bytes(quirks: &Quirks) -> [u8; 6] {
+    [
+        quirks.shift_uses_vy as u8,
+        index_increment_id(quirks.load_store_index),
+        quirks.jump_uses_vx as u8,
+        quirks.vf_reset as u8,
+        quirks.display_wait as u8,
+        (quirks.edge_mode == EdgeMode::Clip) as u8,
+    ]
+}
+
+fn index_increment_id(increment: IndexIncrement) -> u8 {
+    match increment {
+        IndexIncrement::Unchanged => 0,
+        IndexIncrement::PastLast => 1,
+        IndexIncrement::ByX => 2,
+    }
+}
+
+fn index_increment_from_id(id: u8) -> Option<IndexIncrement> {
+    match id {
+        0 => Some(IndexIncrement::Unchanged),
+        1 => Some(IndexIncrement::PastLast),
+        2 => Some(IndexIncrement::ByX),
+        _ => None,
+    }
+}
+
+fn index_increment_name(increment: IndexIncrement) -> &'static str {
+    match increment {
+        IndexIncrement::Unchanged => "unchanged",
+        IndexIncrement::PastLast => "past_last",
+        IndexIncrement::ByX => "by_x",
+    }
+}
+
+/// Sections of a state by tag, in file order
+struct Sections<'a> {
+    sections: Vec<([u8; 4], &'a [u8])>,
+}
+
+impl<'a> Sections<'a> {
+    fn parse(mut data: &'a [u8]) -> Result<Self, StateError> {
+        let mut sections = Vec::new();
+
+        while !data.is_empty() {
+            if data.len() < 8 {
+                return Err(StateError::Truncated);
+            }
+
+            let mut tag = [0; 4];
+            tag.copy_from_slice(&data[..4]);
+            let len = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+            if data.len() - 8 < len {
+                return Err(StateError::Truncated);
+            }
+
+            sections.push((tag, &data[8..8 + len]));
+            data = &data[8 + len..];
+        }
+
+        Ok(Sections { sections })
+    }
+
+    fn get(&self, tag: &[u8; 4]) -> Option<&'a [u8]> {
+        self.sections
+            .iter()
+            .find(|(section, _)| section == tag)
+            .map(|(_, payload)| *payload)
+    }
+
+    fn require(&self, tag: &[u8; 4], len: usize) -> Result<&'a [u8], StateError> {
+        let payload = self
+            .get(tag)
+            .ok_or_else(|| StateError::MissingSection(tag_name(tag)))?;
+
+        if payload.len() != len {
+            return Err(StateError::BadSection(tag_name(tag)));
+        }
+
+        Ok(payload)
+    }
+}
+
+impl Chip8 {
+    /// Snapshot the whole machine as a versioned binary blob, see `load_state`
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&STATE_VERSION.to_le_bytes());
+
+        push_section(&mut out, TAG_VARIANT, &[variant_id(self.variant)]);
+
+        let mut cpu = Vec::with_capacity(CPU_LEN);
+        cpu.extend_from_slice(&self.registers);
+        cpu.extend_from_slice(&self.index.to_le_bytes());
+        cpu.extend_from_slice(&self.pc.to_le_bytes());
+        for address in self.stack.iter() {
+            cpu.extend_from_slice(&address.to_le_bytes());
+        }
+        cpu.push(self.sp);
+        cpu.push(self.delay_timer);
+        cpu.push(self.sound_timer);
+        cpu.extend_from_slice(&self.opcode.to_le_bytes());
+        push_section(&mut out, TAG_CPU, &cpu);
+
+        // only the memory the variant can address
+        push_section(&mut out, TAG_MEMORY, &self.memory[..self.mem_size()]);
+        push_section(&mut out, TAG_KEYPAD, &self.keypad);
+
+        // only the rows and columns of the current video mode
+        let mut video = vec![self.video.hires() as u8, self.planes];
+        for plane in 0..framebuffer::PLANES {
+            video.extend_from_slice(&pack_plane(&self.video, plane));
+        }
+        push_section(&mut out, TAG_VIDEO, &video);
+
+        push_section(&mut out, TAG_QUIRKS, &quirk_bytes(&self.quirks));
+
+        push_section(&mut out, TAG_SCHIP, &self.rpl_flags);
+
+        let mut xochip = vec![self.audio_pattern.is_some() as u8];
+        xochip.extend_from_slice(&self.audio_pattern.unwrap_or([0; 16]));
+        xochip.push(self.pitch);
+        push_section(&mut out, TAG_XOCHIP, &xochip);
+
+        let mut speed = Vec::with_capacity(8);
+        speed.extend_from_slice(&self.speed.to_le_bytes());
+        speed.extend_from_slice(&self.cycle_remainder.to_le_bytes());
+        push_section(&mut out, TAG_SPEED, &speed);
+
+        push_section(&mut out, TAG_RANDOM, &self.rng.state().to_le_bytes());
+
+        out
+    }
+
+    /// Restore a snapshot taken by `save_state`.
+    /// The machine is left untouched when an error is returned
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), StateError> {
+        if data.len() < 6 {
+            return Err(StateError::Truncated);
+        }
+        if &data[..4] != MAGIC {
+            return Err(StateError::BadMagic);
+        }
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        if version != STATE_VERSION {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+
+        let sections = Sections::parse(&data[6..])?;
+
+        // validate everything before touching the machine
+        let variant = match sections.require(TAG_VARIANT, 1)?[0] {
+            0 => Variant::Chip8,
+            1 => Variant::SuperChip,
+            2 => Variant::XoChip,
+            _ => return Err(StateError::BadSection(tag_name(TAG_VARIANT))),
+        };
+        let cpu = sections.require(TAG_CPU, CPU_LEN)?;
+        let memory = sections.require(TAG_MEMORY, variant.mem_size())?;
+        let keypad = sections.require(TAG_KEYPAD, 16)?;
+        let hires = sections
+            .get(TAG_VIDEO)
+            .and_then(|video| video.first())
+            .is_some_and(|hires| *hires != 0);
+        let plane_len = plane_len(hires);
+        let video = sections.require(TAG_VIDEO, 2 + plane_len * framebuffer::PLANES)?;
+        let quirks = sections.require(TAG_QUIRKS, 6)?;
+        let schip = sections.require(TAG_SCHIP, 16)?;
+        let xochip = sections.require(TAG_XOCHIP, 18)?;
+        let speed = sections.require(TAG_SPEED, 8)?;
+        let random = sections.require(TAG_RANDOM, 4)?;
+
+        let sp = cpu[52];
+        if sp as usize > self.stack.len() {
+            return Err(StateError::BadSection(tag_name(TAG_CPU)));
+        }
+        let load_store_index = index_increment_from_id(quirks[1])
+            .ok_or_else(|| StateError::BadSection(tag_name(TAG_QUIRKS)))?;
+
+        self.variant = variant;
+
+        self.registers.copy_from_slice(&cpu[..16]);
+        self.index = u16::from_le_bytes([cpu[16], cpu[17]]);
+        self.pc = u16::from_le_bytes([cpu[18], cpu[19]]);
+        for (i, address) in self.stack.iter_mut().enumerate() {
+            *address = u16::from_le_bytes([cpu[20 + i * 2], cpu[21 + i * 2]]);
+        }
+        self.sp = sp;
+        self.delay_timer = cpu[53];
+        self.sound_timer = cpu[54];
+        self.opcode = u16::from_le_bytes([cpu[55], cpu[56]]);
+
+        self.memory.fill(0);
+        self.memory[..memory.len()].copy_from_slice(memory);
+        self.keypad.copy_from_slice(keypad);
+
+        self.video.set_hires(hires);
+        self.planes = video[1] & 0b11;
+        for (plane, packed) in video[2..].chunks(plane_len).enumerate() {
+            unpack_plane(packed, &mut self.video, plane);
+        }
+
+        self.quirks = Quirks {
+            shift_uses_vy: quirks[0] != 0,
+            load_store_index,
+            jump_uses_vx: quirks[2] != 0,
+            vf_reset: quirks[3] != 0,
+            display_wait: quirks[4] != 0,
+            edge_mode: if quirks[5] != 0 {
+                EdgeMode::Clip
+            } else {
+                EdgeMode::Wrap
+            },
+        };
+
+        self.rpl_flags.copy_from_slice(schip);
+
+        let mut pattern = [0; 16];
+        pattern.copy_from_slice(&xochip[1..17]);
+        self.audio_pattern = if xochip[0] != 0 { Some(pattern) } else { None };
+        self.pitch = xochip[17];
+
+        self.speed = u32::from_le_bytes([speed[0], speed[1], speed[2], speed[3]]);
+        self.cycle_remainder = u32::from_le_bytes([speed[4], speed[5], speed[6], speed[7]]);
+        // states are taken between frames, the next instruction starts one
+        self.frame_cycles = 0;
+
+        self.rng = XorShift::new(u32::from_le_bytes([random[0], random[1], random[2], random[3]]));
+
+        Ok(())
+    }
+
+    /// The same snapshot as `save_state` as human readable JSON, for bug reports and diffing.
+    /// Video planes are rows of color indices, see `color_index`
+    pub fn save_state_json(&self) -> String {
+        let numbers = |values: &mut dyn Iterator<Item = u32>| {
+            values.map(|value| value.to_string()).collect::<Vec<_>>().join(", ")
+        };
+
+        let mut json = String::from("{\n");
+        json.push_str(&format!("  \"version\": {},\n", STATE_VERSION));
+        json.push_str(&format!("  \"variant\": \"{}\",\n", variant_name(self.variant)));
+        json.push_str(&format!(
+            "  \"registers\": [{}],\n",
+            numbers(&mut self.registers.iter().map(|v| *v as u32))
+        ));
+        json.push_str(&format!("  \"index\": {},\n", self.index));
+        json.push_str(&format!("  \"pc\": {},\n", self.pc));
+        json.push_str(&format!(
+            "  \"stack\": [{}],\n",
+            numbers(&mut self.stack.iter().map(|v| *v as u32))
+        ));
+        json.push_str(&format!("  \"sp\": {},\n", self.sp));
+        json.push_str(&format!("  \"delay_timer\": {},\n", self.delay_timer));
+        json.push_str(&format!("  \"sound_timer\": {},\n", self.sound_timer));
+        json.push_str(&format!("  \"opcode\": {},\n", self.opcode));
+        json.push_str(&format!(
+            "  \"keypad\": [{}],\n",
+            numbers(&mut self.keypad.iter().map(|v| *v as u32))
+        ));
+        json.push_str(&format!("  \"speed\": {},\n", self.speed));
+        json.push_str(&format!("  \"rng_state\": {},\n", self.rng.state()));
+
+        let q = &self.quirks;
+        let quirks = [
+            ("shift_uses_vy", q.shift_uses_vy.to_string()),
+            (
+                "load_store_index",
+                format!("\"{}\"", index_increment_name(q.load_store_index)),
+            ),
+            ("jump_uses_vx", q.jump_uses_vx.to_string()),
+            ("vf_reset", q.vf_reset.to_string()),
+            ("display_wait", q.display_wait.to_string()),
+            ("clip_sprites", (q.edge_mode == EdgeMode::Clip).to_string()),
+        ];
+        let quirks: Vec<String> = quirks
+            .iter()
+            .map(|(name, value)| format!("\"{}\": {}", name, value))
+            .collect();
+        json.push_str(&format!("  \"quirks\": {{{}}},\n", quirks.join(", ")));
+
+        json.push_str(&format!(
+            "  \"rpl_flags\": [{}],\n",
+            numbers(&mut self.rpl_flags.iter().map(|v| *v as u32))
+        ));
+        match &self.audio_pattern {
+            Some(pattern) => json.push_str(&format!(
+                "  \"audio_pattern\": [{}],\n",
+                numbers(&mut pattern.iter().map(|v| *v as u32))
+            )),
+            None => json.push_str("  \"audio_pattern\": null,\n"),
+        }
+        json.push_str(&format!("  \"pitch\": {},\n", self.pitch));
+
+        json.push_str(&format!("  \"hires\": {},\n", self.video.hires()));
+        json.push_str(&format!("  \"planes\": {},\n", self.planes));
+        let rows: Vec<String> = (0..self.height())
+            .map(|y| {
+                let row: String = (0..self.width())
+                    .map(|x| std::char::from_digit(self.color_index(x, y) as u32, 10).unwrap_or('?'))
+                    .collect();
+                format!("    \"{}\"", row)
+            })
+            .collect();
+        json.push_str(&format!("  \"video\": [\n{}\n  ],\n", rows.join(",\n")));
+
+        json.push_str(&format!(
+            "  \"memory\": [{}]\n",
+            numbers(&mut self.memory[..self.mem_size()].iter().map(|v| *v as u32))
+        ));
+        json.push('}');
+
+        json
+    }
+}
@@ -0,0 +1,170 @@
+// Tests
+#[cfg(test)]
+mod test_asm {
+    use super::super::asm::{self, AsmError};
+    use super::super::chip8::{Chip8, Variant};
+    use super::super::disasm;
+
+    fn bytes(source: &str) -> Vec<u8> {
+        asm::assemble(source).unwrap()
+    }
+
+    fn error(source: &str) -> AsmError {
+        asm::assemble(source).unwrap_err()
+    }
+
+    #[test]
+    fn test_assemble_mnemonics() {
+        assert_eq!(bytes("CLS"), [0x00, 0xE0]);
+        assert_eq!(bytes("RET"), [0x00, 0xEE]);
+        assert_eq!(bytes("JMP 0x234"), [0x12, 0x34]);
+        assert_eq!(bytes("JP 0x234"), [0x12, 0x34]);
+        assert_eq!(bytes("CALL 0xABC"), [0x2A, 0xBC]);
+        assert_eq!(bytes("SE VA, 0x07"), [0x3A, 0x07]);
+        assert_eq!(bytes("SNE V1, 255"), [0x41, 0xFF]);
+        assert_eq!(bytes("SE V1, V2"), [0x51, 0x20]);
+        assert_eq!(bytes("LD VF, 0x10"), [0x6F, 0x10]);
+        assert_eq!(bytes("ADD V3, 1"), [0x73, 0x01]);
+        assert_eq!(bytes("LD V3, V4"), [0x83, 0x40]);
+        assert_eq!(bytes("ADD V3, V4"), [0x83, 0x44]);
+        assert_eq!(bytes("SUBN V3, V4"), [0x83, 0x47]);
+        assert_eq!(bytes("SHR V3, V4"), [0x83, 0x46]);
+        assert_eq!(bytes("SHL V3"), [0x83, 0x0E]);
+        assert_eq!(bytes("SNE VE, VD"), [0x9E, 0xD0]);
+        assert_eq!(bytes("LD I, 0x2B4"), [0xA2, 0xB4]);
+        assert_eq!(bytes("JP V0, 0x300"), [0xB3, 0x00]);
+        assert_eq!(bytes("RND V0, 0x0F"), [0xC0, 0x0F]);
+        assert_eq!(bytes("DRW V0, V1, 5"), [0xD0, 0x15]);
+        assert_eq!(bytes("SKP V2"), [0xE2, 0x9E]);
+        assert_eq!(bytes("SKNP V2"), [0xE2, 0xA1]);
+        assert_eq!(bytes("LD V5, DT"), [0xF5, 0x07]);
+        assert_eq!(bytes("LD V5, K"), [0xF5, 0x0A]);
+        assert_eq!(bytes("LD DT, V5"), [0xF5, 0x15]);
+        assert_eq!(bytes("LD ST, V5"), [0xF5, 0x18]);
+        assert_eq!(bytes("ADD I, V5"), [0xF5, 0x1E]);
+        assert_eq!(bytes("LD F, V5"), [0xF5, 0x29]);
+        assert_eq!(bytes("LD B, V5"), [0xF5, 0x33]);
+        assert_eq!(bytes("LD [I], V5"), [0xF5, 0x55]);
+        assert_eq!(bytes("LD V5, [I]"), [0xF5, 0x65]);
+        assert_eq!(bytes("ld v5, [i]"), [0xF5, 0x65]);
+    }
+
+    #[test]
+    fn test_assemble_extensions() {
+        assert_eq!(bytes("SCD 4"), [0x00, 0xC4]);
+        assert_eq!(bytes("SCR"), [0x00, 0xFB]);
+        assert_eq!(bytes("SCL"), [0x00, 0xFC]);
+        assert_eq!(bytes("EXIT"), [0x00, 0xFD]);
+        assert_eq!(bytes("LOW"), [0x00, 0xFE]);
+        assert_eq!(bytes("HIGH"), [0x00, 0xFF]);
+        assert_eq!(bytes("LD HF, V1"), [0xF1, 0x30]);
+        assert_eq!(bytes("LD R, V1"), [0xF1, 0x75]);
+        assert_eq!(bytes("LD V1, R"), [0xF1, 0x85]);
+        assert_eq!(bytes("SAVE V2 - V5"), [0x52, 0x52]);
+        assert_eq!(bytes("LOAD V5 - V2"), [0x55, 0x23]);
+        assert_eq!(bytes("LD I, long 0xABCD"), [0xF0, 0x00, 0xAB, 0xCD]);
+        assert_eq!(bytes("PLANE 3"), [0xF3, 0x01]);
+        assert_eq!(bytes("AUDIO"), [0xF0, 0x02]);
+        assert_eq!(bytes("PITCH V4"), [0xF4, 0x3A]);
+    }
+
+    #[test]
+    fn test_labels_and_constants() {
+        let source = "
+            ; draw a box forever
+            X = 8
+            Y = X + 4
+            start:
+                LD V0, X
+                LD V1, Y
+                LD I, box       ; forward reference
+                DRW V0, V1, 2
+            loop: JMP loop
+            box:
+                db 0xF0, 0b10010000
+                dw 0x1234, start
+                db box - start
+        ";
+        assert_eq!(
+            bytes(source),
+            [
+                0x60, 0x08, 0x61, 0x0C, 0xA2, 0x0A, 0xD0, 0x12, 0x12, 0x08, 0xF0, 0x90, 0x12, 0x34, 0x02, 0x00, 0x0A
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_load_moves_labels() {
+        let source = "LD I, long data\nJMP end\nend: db 1\ndata: db 2";
+        assert_eq!(bytes(source), [0xF0, 0x00, 0x02, 0x07, 0x12, 0x06, 0x01, 0x02]);
+    }
+
+    #[test]
+    fn test_errors_have_line_numbers() {
+        let err = error("CLS\n\nFOO V1");
+        assert_eq!(err.line, 3);
+        assert_eq!(err.to_string(), "line 3: unknown instruction 'FOO'");
+
+        assert_eq!(error("CLS\nJMP nowhere").line, 2);
+        assert!(error("CLS\nJMP nowhere").message.contains("nowhere"));
+        assert_eq!(error("LD V0, 256").line, 1);
+        assert_eq!(error("JMP 0x1000").line, 1);
+        assert_eq!(error("DRW V0, V1, 16").line, 1);
+        assert_eq!(error("CLS\nLD V0").line, 2);
+        assert!(error("LD V0").message.contains("invalid operands"));
+        assert_eq!(error("a: CLS\na: CLS").line, 2);
+        assert_eq!(error("A = C\nC = A\nLD V0, A").line, 3);
+        assert_eq!(error("db").line, 1);
+        assert_eq!(error("2bad: CLS").line, 1);
+    }
+
+    #[test]
+    fn test_reserved_names() {
+        // each would define fine but read back as a register or keyword wherever it's used
+        for name in ["B", "F", "K", "R", "I", "DT", "ST", "HF", "va", "V0", "dt"].iter() {
+            let err = error(&format!("CLS\n{}: CLS", name));
+            assert_eq!(err.line, 2, "{}", name);
+            assert!(err.message.contains("register or keyword"), "{}", name);
+
+            let err = error(&format!("{} = 0x300", name));
+            assert_eq!(err.line, 1, "{}", name);
+            assert!(err.message.contains("register or keyword"), "{}", name);
+        }
+
+        // names that only start like one are fine
+        assert!(asm::assemble("V10 = 1\nBX: JP BX\nLD V0, V10").is_ok());
+    }
+
+    #[test]
+    fn test_assembled_rom_runs() {
+        let source = "
+                LD V0, 0
+                LD V1, 5
+            loop:
+                ADD V0, V1
+                SE V0, 25
+                JMP loop
+            done:
+                JMP done
+        ";
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&bytes(source)).unwrap();
+        for _ in 0..20 {
+            chip.cycle().unwrap();
+        }
+        assert_eq!(chip.registers[0], 25);
+        assert_eq!(chip.pc, 0x20A);
+    }
+
+    #[test]
+    fn test_disassembly_round_trips() {
+        for rom in [&include_bytes!("tetris.ch8")[..], &include_bytes!("BC_test.ch8")[..], &include_bytes!("test_opcode.ch8")[..]].iter() {
+            let text = disasm::disassemble(rom, Variant::Chip8);
+            assert_eq!(&asm::assemble(&text).unwrap()[..], &rom[..]);
+        }
+
+        let xo = [0xF0, 0x00, 0x02, 0x08, 0xF3, 0x01, 0x12, 0x06, 0x55, 0xAA];
+        let text = disasm::disassemble(&xo, Variant::XoChip);
+        assert_eq!(asm::assemble(&text).unwrap(), xo);
+    }
+}
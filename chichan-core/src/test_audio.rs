@@ -0,0 +1,72 @@
+// Tests
+#[cfg(test)]
+mod test_audio {
+    use super::super::audio::{AudioSink, Buzzer, WavBuffer};
+    use super::super::chip8::Chip8;
+
+    #[test]
+    fn test_is_beeping() {
+        let mut chip = Chip8::new();
+        assert!(!chip.is_beeping());
+
+        chip.sound_timer = 1;
+        assert!(chip.is_beeping());
+
+        chip.tick_timers();
+        assert!(!chip.is_beeping());
+    }
+
+    #[test]
+    fn test_square_wave() {
+        let mut buzzer = Buzzer::new(8);
+        buzzer.frequency = 2.0;
+        buzzer.volume = 0.5;
+
+        let samples: Vec<f32> = (0..8).map(|_| buzzer.next_sample(true)).collect();
+
+        assert_eq!(samples, vec![0.5, 0.5, -0.5, -0.5, 0.5, 0.5, -0.5, -0.5]);
+    }
+
+    #[test]
+    fn test_silent_when_not_beeping() {
+        let mut buzzer = Buzzer::new(44100);
+        let mut wav = WavBuffer::new(44100);
+
+        buzzer.render(false, 100, &mut wav);
+
+        assert_eq!(wav.samples.len(), 100);
+        assert!(wav.samples.iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let mut chip = Chip8::new();
+        let mut buzzer = Buzzer::new(44100);
+        let mut wav = WavBuffer::new(44100);
+
+        chip.sound_timer = 1;
+        buzzer.render_frame(&chip, &mut wav);
+        chip.tick_timers();
+        buzzer.render_frame(&chip, &mut wav);
+
+        assert_eq!(wav.samples.len(), 735 * 2);
+        assert!(wav.samples[..735].iter().any(|sample| *sample != 0.0));
+        assert!(wav.samples[735..].iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn test_wav_encoding() {
+        let mut wav = WavBuffer::new(8000);
+        wav.write_samples(&[0.0, 1.0, -1.0]);
+
+        let bytes = wav.to_wav();
+
+        assert_eq!(bytes.len(), 44 + 6);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes([bytes[24], bytes[25], bytes[26], bytes[27]]), 8000);
+        assert_eq!(u32::from_le_bytes([bytes[40], bytes[41], bytes[42], bytes[43]]), 6);
+        assert_eq!(i16::from_le_bytes([bytes[46], bytes[47]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([bytes[48], bytes[49]]), -i16::MAX);
+    }
+}
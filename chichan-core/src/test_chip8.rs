@@ -0,0 +1,467 @@
+// Tests
+#[allow(dead_code)]
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test_chip8 {
+    use super::super::chip8::{Chip8, Chip8Error, RomError, StepEvent, UnknownOpcode, UnknownOpcodePolicy};
+
+    const MEM_SIZE: usize = 4096;
+    const START_ADDRESS: usize = 0x200;
+    const FONT_SET_START_ADDRESS: usize = 0x50;
+    const VIDEO_WIDTH: usize = 64;
+    const VIDEO_HEIGHT: usize = 32;
+    const VIDEO_SIZE: usize = VIDEO_WIDTH * VIDEO_HEIGHT;
+     
+    #[test]
+    fn test_video_rows() {
+        // init
+        let mut chip = Chip8::new();
+        chip.video.set(0, 0, 0, true);
+        chip.video.set(0, 0, 1, true);
+
+        let rows: Vec<Vec<usize>> = chip.video.rows().map(|row| row.collect()).collect();
+
+        //test
+        assert_eq!(rows.len(), VIDEO_HEIGHT);
+        assert_eq!(rows[0].len(), VIDEO_WIDTH);
+        assert_eq!(rows[0][0], 1);
+        assert_eq!(rows[1][0], 1);
+        assert_eq!(rows[0][1], 0);
+    }
+
+    #[test]
+    fn test_OP_00E0() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 0, 0, true);
+        chip.video.set(0, VIDEO_WIDTH - 1, VIDEO_HEIGHT - 1, true);
+
+        Chip8::OP_00E0(&mut chip).unwrap();
+
+        assert!(chip.video.rows().flatten().all(|color| color == 0));
+    }
+
+    #[test]
+    fn test_OP_8xy0() {
+        //init
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 2;
+        chip.registers[0x3] = 5;
+        chip.opcode = 0x8130;
+        //test
+        chip.OP_8xy0().unwrap();
+
+        //assert
+        assert_eq!(chip.registers[0x1], chip.registers[0x3]);
+    }
+
+    #[test]
+    fn test_OP_8xy1() {
+        //init
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0b0011;
+        chip.registers[0x2] = 0b1100;
+        chip.opcode = 0x8121;
+
+        //test
+        chip.OP_8xy1().unwrap();
+
+        //assert
+        assert_eq!(0b1111, chip.registers[0x1]);
+    }
+
+    #[test]
+    fn test_OP_8xy2() {
+        //init
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0b0011;
+        chip.registers[0x3] = 0b0001;
+        chip.opcode = 0x8132;
+
+        //test
+        chip.OP_8xy2().unwrap();
+        let vx = chip.registers[0x1];
+        let res: u8 = 0b0001;
+
+
+        //assert
+        assert_eq!(vx, res);
+        
+
+    }
+
+    #[test]
+    fn test_OP_8xy3() {
+        //init
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0b0011;
+        chip.registers[0x3] = 0b0001;
+        chip.opcode = 0x8133;
+
+        //test
+        chip.OP_8xy3().unwrap();
+        let vx = chip.registers[0x1];
+        let res: u8 = 0b0010;
+        assert_eq!(vx, res);
+    }
+
+    #[test]
+    fn test_OP_fx55() {
+        //init
+        let mut chip = Chip8::new();
+        chip.registers[0x0] = 0x0;
+        chip.registers[0x1] = 0xA;
+        chip.opcode = 0xF355;
+        chip.index = 1000;
+
+        //test
+        chip.OP_Fx55().unwrap();
+        let v0 = chip.registers[0x0];
+        let v1 = chip.registers[0x1];
+        
+        let m0 = chip.memory[chip.index as usize];
+        let m1 = chip.memory[chip.index as usize + 1];
+
+        assert_eq!(v0, m0);
+        assert_eq!(v1, m1);
+    }
+
+    #[test]
+    fn test_load_rom_bytes() {
+        let mut chip = Chip8::new();
+        let rom = [0x00, 0xE0, 0x12, 0x00];
+
+        let loaded = chip.load_rom_bytes(&rom).unwrap();
+
+        assert_eq!(loaded, rom.len());
+        assert_eq!(&chip.memory[START_ADDRESS..START_ADDRESS + rom.len()], &rom);
+    }
+
+    #[test]
+    fn test_load_rom_bytes_fills_memory() {
+        let mut chip = Chip8::new();
+        let rom = vec![0xAB; MEM_SIZE - START_ADDRESS];
+
+        assert_eq!(chip.load_rom_bytes(&rom).unwrap(), 3584);
+        assert_eq!(chip.memory[MEM_SIZE - 1], 0xAB);
+    }
+
+    #[test]
+    fn test_load_rom_bytes_empty() {
+        let mut chip = Chip8::new();
+
+        assert!(matches!(chip.load_rom_bytes(&[]), Err(RomError::Empty)));
+    }
+
+    #[test]
+    fn test_load_rom_bytes_too_large() {
+        let mut chip = Chip8::new();
+        let rom = vec![0; MEM_SIZE - START_ADDRESS + 1];
+
+        match chip.load_rom_bytes(&rom) {
+            Err(RomError::TooLarge { size, available }) => {
+                assert_eq!(size, 3585);
+                assert_eq!(available, 3584);
+            }
+            other => panic!("expected TooLarge, got {:?}", other),
+        }
+        assert_eq!(chip.memory[START_ADDRESS], 0);
+    }
+
+    #[test]
+    fn test_load_rom_missing_file() {
+        let mut chip = Chip8::new();
+
+        assert!(matches!(chip.load_rom("./src/missing.ch8"), Err(RomError::Io(_))));
+    }
+
+    #[test]
+    fn test_load_rom() {
+        let mut chip = Chip8::new();
+
+        let loaded = chip.load_rom("./src/test_opcode.ch8").unwrap();
+
+        assert_eq!(loaded, 478);
+        assert_eq!(chip.memory[START_ADDRESS], 0x12);
+        assert_eq!(chip.memory[START_ADDRESS + 1], 0x4E);
+    }
+
+    #[test]
+    fn test_OP_00EE_empty_stack() {
+        let mut chip = Chip8::new();
+        chip.opcode = 0x00EE;
+
+        assert_eq!(chip.OP_00EE(), Err(Chip8Error::StackUnderflow));
+    }
+
+    #[test]
+    fn test_OP_2nnn_full_stack() {
+        let mut chip = Chip8::new();
+        chip.opcode = 0x2300;
+
+        for _ in 0..16 {
+            chip.OP_2nnn().unwrap();
+        }
+
+        assert_eq!(chip.OP_2nnn(), Err(Chip8Error::StackOverflow));
+        assert_eq!(chip.sp, 16);
+    }
+
+    #[test]
+    fn test_OP_7xkk_wraps() {
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0xFF;
+        chip.opcode = 0x7102;
+
+        chip.OP_7xkk().unwrap();
+
+        assert_eq!(chip.registers[0x1], 0x01);
+        assert_eq!(chip.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_OP_8xy4_carry() {
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0xF0;
+        chip.registers[0x2] = 0x20;
+        chip.opcode = 0x8124;
+
+        chip.OP_8xy4().unwrap();
+
+        assert_eq!(chip.registers[0x1], 0x10);
+        assert_eq!(chip.registers[0xF], 1);
+    }
+
+    #[test]
+    fn test_OP_8xy5_borrow() {
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0x01;
+        chip.registers[0x2] = 0x02;
+        chip.opcode = 0x8125;
+
+        chip.OP_8xy5().unwrap();
+
+        assert_eq!(chip.registers[0x1], 0xFF);
+        assert_eq!(chip.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_OP_8xy7_borrow() {
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0x02;
+        chip.registers[0x2] = 0x01;
+        chip.opcode = 0x8127;
+
+        chip.OP_8xy7().unwrap();
+
+        assert_eq!(chip.registers[0x1], 0xFF);
+        assert_eq!(chip.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_memory_out_of_bounds() {
+        let mut chip = Chip8::new();
+        chip.index = (MEM_SIZE - 2) as u16;
+
+        chip.opcode = 0xF255;
+        assert_eq!(chip.OP_Fx55(), Err(Chip8Error::MemoryOutOfBounds { addr: MEM_SIZE }));
+
+        chip.opcode = 0xF265;
+        assert_eq!(chip.OP_Fx65(), Err(Chip8Error::MemoryOutOfBounds { addr: MEM_SIZE }));
+
+        chip.opcode = 0xF033;
+        assert_eq!(chip.OP_Fx33(), Err(Chip8Error::MemoryOutOfBounds { addr: MEM_SIZE }));
+    }
+
+    #[test]
+    fn test_cycle_pc_at_end_of_memory() {
+        let mut chip = Chip8::new();
+        chip.pc = 0xFFF;
+
+        assert_eq!(chip.cycle(), Err(Chip8Error::MemoryOutOfBounds { addr: MEM_SIZE }));
+    }
+
+    #[test]
+    fn test_cycle_invalid_opcode() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0xE0, 0x00]).unwrap();
+
+        assert_eq!(
+            chip.cycle(),
+            Err(Chip8Error::InvalidOpcode { opcode: 0xE000, pc: START_ADDRESS as u16 })
+        );
+    }
+
+    #[test]
+    fn test_unknown_opcode_ignored() {
+        let mut chip = Chip8::new();
+        chip.unknown_opcode_policy = UnknownOpcodePolicy::Ignore;
+        chip.load_rom_bytes(&[0x80, 0x08, 0xF0, 0xFF, 0x60, 0x01]).unwrap();
+
+        assert_eq!(chip.cycle(), Ok(StepEvent::UnknownOpcode));
+        assert_eq!(chip.cycle(), Ok(StepEvent::UnknownOpcode));
+        assert_eq!(chip.cycle(), Ok(StepEvent::Executed));
+
+        assert_eq!(chip.registers[0x0], 0x01);
+        assert_eq!(chip.unknown_opcode_count, 2);
+        assert_eq!(
+            chip.last_unknown_opcode,
+            Some(UnknownOpcode { opcode: 0xF0FF, pc: START_ADDRESS as u16 + 2 })
+        );
+    }
+
+    #[test]
+    fn test_unknown_opcode_halts() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0xE1, 0x00]).unwrap();
+
+        assert_eq!(
+            chip.cycle(),
+            Err(Chip8Error::InvalidOpcode { opcode: 0xE100, pc: START_ADDRESS as u16 })
+        );
+        assert_eq!(chip.unknown_opcode_count, 1);
+    }
+
+    #[test]
+    fn test_unknown_opcode_count_saturates() {
+        let mut chip = Chip8::new();
+        chip.unknown_opcode_policy = UnknownOpcodePolicy::Ignore;
+        chip.load_rom_bytes(&[0xF0, 0xFF]).unwrap();
+        chip.unknown_opcode_count = u32::MAX;
+
+        assert_eq!(chip.cycle(), Ok(StepEvent::UnknownOpcode));
+        assert_eq!(chip.unknown_opcode_count, u32::MAX);
+    }
+
+    #[test]
+    fn test_extensions_reported_as_unknown() {
+        // SUPER-CHIP and XO-CHIP roms run as plain CHIP-8 report what they need
+        for opcode in [0x00FFu16, 0x00C2, 0xF000] {
+            let mut chip = Chip8::new();
+            chip.unknown_opcode_policy = UnknownOpcodePolicy::Log;
+            chip.load_rom_bytes(&opcode.to_be_bytes()).unwrap();
+
+            assert_eq!(chip.cycle(), Ok(StepEvent::UnknownOpcode));
+            assert_eq!(chip.unknown_opcode_count, 1);
+            assert_eq!(
+                chip.last_unknown_opcode,
+                Some(UnknownOpcode { opcode, pc: START_ADDRESS as u16 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_opcode_count_reset_on_load() {
+        let mut chip = Chip8::new();
+        chip.unknown_opcode_policy = UnknownOpcodePolicy::Ignore;
+        chip.load_rom_bytes(&[0xF0, 0xFF]).unwrap();
+        chip.cycle().unwrap();
+
+        chip.load_rom_bytes(&[0x00, 0xE0]).unwrap();
+
+        assert_eq!(chip.unknown_opcode_count, 0);
+        assert_eq!(chip.last_unknown_opcode, None);
+    }
+
+    #[test]
+    fn test_cycle_waiting_for_key() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0xF3, 0x0A]).unwrap();
+
+        assert_eq!(chip.cycle(), Ok(StepEvent::WaitingForKey));
+        assert_eq!(chip.pc, START_ADDRESS as u16);
+
+        chip.keypad[0x7] = 1;
+
+        assert_eq!(chip.cycle(), Ok(StepEvent::Executed));
+        assert_eq!(chip.registers[0x3], 0x7);
+    }
+
+    #[test]
+    fn test_run_frame_instruction_count() {
+        let mut chip = Chip8::new();
+        // ADD V0, 1 over and over
+        let rom: Vec<u8> = [0x70, 0x01].iter().cycle().take(200).cloned().collect();
+        chip.load_rom_bytes(&rom).unwrap();
+        chip.speed = 90;
+
+        chip.run_frame().unwrap();
+        assert_eq!(chip.registers[0x0], 1);
+
+        chip.run_frame().unwrap();
+        assert_eq!(chip.registers[0x0], 3);
+    }
+
+    #[test]
+    fn test_run_frame_finishes_a_stepped_frame() {
+        let mut chip = Chip8::new();
+        // ADD V0, 1 over and over, 10 a frame
+        let rom: Vec<u8> = [0x70, 0x01].iter().cycle().take(200).cloned().collect();
+        chip.load_rom_bytes(&rom).unwrap();
+        chip.speed = 600;
+        chip.delay_timer = 10;
+
+        for _ in 0..3 {
+            chip.step().unwrap();
+        }
+        assert_eq!(chip.delay_timer, 10);
+
+        chip.run_frame().unwrap();
+        assert_eq!(chip.registers[0x0], 10);
+        assert_eq!(chip.delay_timer, 9);
+    }
+
+    #[test]
+    fn test_run_frame_at_max_speed() {
+        let mut chip = Chip8::new();
+        // DRW V0, V0, 1 ends the frame with display_wait
+        chip.load_rom_bytes(&[0xD0, 0x01]).unwrap();
+        chip.quirks.display_wait = true;
+        chip.speed = u32::MAX;
+        chip.cycle_remainder = 59;
+
+        assert_eq!(chip.run_frame(), Ok(StepEvent::Drew));
+        assert_eq!(chip.cycle_remainder, ((u32::MAX as u64 + 59) % 60) as u32);
+    }
+
+    #[test]
+    fn test_run_frame_ticks_timers_once() {
+        let mut chip = Chip8::new();
+        // JP 0x200
+        chip.load_rom_bytes(&[0x12, 0x00]).unwrap();
+        chip.speed = 1000;
+        chip.delay_timer = 10;
+        chip.sound_timer = 1;
+
+        chip.run_frame().unwrap();
+
+        assert_eq!(chip.delay_timer, 9);
+        assert_eq!(chip.sound_timer, 0);
+
+        chip.run_frame().unwrap();
+
+        assert_eq!(chip.delay_timer, 8);
+        assert_eq!(chip.sound_timer, 0);
+    }
+
+    #[test]
+    fn test_cycle_leaves_timers_alone() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x12, 0x00]).unwrap();
+        chip.delay_timer = 10;
+
+        chip.cycle().unwrap();
+
+        assert_eq!(chip.delay_timer, 10);
+    }
+
+    #[test]
+    fn test_machine_size() {
+        // what the docs promise a no_std target has to find room for
+        assert!(std::mem::size_of::<Chip8>() <= 71 * 1024);
+    }
+
+    // #[test]
+    // fn test_OP_fx33() {
+    //     unimplemented!();
+    // }
+}
@@ -0,0 +1,231 @@
+// Tests
+#[cfg(test)]
+mod test_debugger {
+    use super::super::chip8::{Chip8, UnknownOpcodePolicy, Variant};
+    use super::super::debugger::{CompareOp, Condition, Debugger, Stop};
+
+    // 200: V0 += 1
+    // 202: CALL 20A
+    // 204: SE V0, 5
+    // 206: JP 200
+    // 208: JP 208
+    // 20A: LD I, 300
+    // 20C: LD B, V0
+    // 20E: RET
+    const ROM: [u8; 16] = [
+        0x70, 0x01, 0x22, 0x0A, 0x30, 0x05, 0x12, 0x00, 0x12, 0x08, 0xA3, 0x00, 0xF0, 0x33, 0x00, 0xEE,
+    ];
+
+    fn debugger() -> Debugger {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&ROM).unwrap();
+        Debugger::new(chip)
+    }
+
+    #[test]
+    fn test_step() {
+        let mut debugger = debugger();
+
+        assert_eq!(debugger.step(), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x202);
+        assert_eq!(debugger.chip.registers[0x0], 1);
+
+        assert_eq!(debugger.step(), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x20A);
+        assert_eq!(debugger.chip.sp, 1);
+    }
+
+    #[test]
+    fn test_step_over_and_out() {
+        let mut debugger = debugger();
+        debugger.step();
+
+        // the whole subroutine runs
+        assert_eq!(debugger.step_over(1000), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x204);
+        assert_eq!(debugger.chip.sp, 0);
+        assert_eq!(debugger.chip.memory[0x302], 1);
+
+        // not a call, a plain step
+        assert_eq!(debugger.step_over(1000), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x206);
+
+        debugger.step();
+        debugger.step();
+        debugger.step();
+        assert_eq!(debugger.chip.pc, 0x20A);
+        assert_eq!(debugger.step_out(1000), Ok(Stop::Done));
+        assert_eq!(debugger.chip.pc, 0x204);
+
+        // back at the top level nothing runs
+        assert!(debugger.step_out(1000).is_err());
+        assert_eq!(debugger.chip.pc, 0x204);
+        assert!(!debugger.is_paused());
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        let mut debugger = debugger();
+        debugger.add_breakpoint(0x20C);
+
+        assert_eq!(debugger.cont(1000), Stop::Breakpoint(0x20C));
+        assert!(debugger.is_paused());
+        assert_eq!(debugger.chip.pc, 0x20C);
+
+        // continuing runs the instruction under the breakpoint
+        assert_eq!(debugger.cont(1000), Stop::Breakpoint(0x20C));
+        assert_eq!(debugger.chip.registers[0x0], 2);
+
+        assert!(debugger.remove_breakpoint(0x20C));
+        assert!(!debugger.remove_breakpoint(0x20C));
+        assert_eq!(debugger.cont(100), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x208);
+    }
+
+    #[test]
+    fn test_conditional_breakpoint() {
+        let mut debugger = debugger();
+        let condition: Condition = "V0 == 3".parse().unwrap();
+        debugger.add_conditional_breakpoint(0x202, Some(condition));
+
+        assert_eq!(debugger.cont(1000), Stop::Breakpoint(0x202));
+        assert_eq!(debugger.chip.registers[0x0], 3);
+    }
+
+    #[test]
+    fn test_watchpoint() {
+        let mut debugger = debugger();
+        debugger.add_watchpoint(0x301, 1);
+
+        assert_eq!(debugger.cont(1000), Stop::Watchpoint { addr: 0x301, pc: 0x20C });
+        assert_eq!(debugger.chip.pc, 0x20E);
+
+        // Fx55 is watched too
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0xA4, 0x00, 0xF3, 0x55]).unwrap();
+        let mut debugger = Debugger::new(chip);
+        debugger.add_watchpoint(0x403, 4);
+        assert_eq!(debugger.cont(10), Stop::Watchpoint { addr: 0x403, pc: 0x202 });
+
+        assert!(debugger.remove_watchpoint(0x403));
+        assert!(debugger.watchpoints().is_empty());
+    }
+
+    #[test]
+    fn test_watchpoint_ignores_unknown_opcodes() {
+        // 5xy2 saves registers on XO-CHIP only, elsewhere it's skipped and writes nothing
+        let rom = [0xA3, 0x00, 0x50, 0x32, 0x12, 0x04];
+        let mut chip = Chip8::new();
+        chip.unknown_opcode_policy = UnknownOpcodePolicy::Ignore;
+        chip.load_rom_bytes(&rom).unwrap();
+        let mut debugger = Debugger::new(chip);
+        debugger.add_watchpoint(0x300, 4);
+        assert_eq!(debugger.cont(10), Stop::Done);
+        assert_eq!(debugger.chip.memory[0x300], 0);
+
+        let mut chip = Chip8::new();
+        chip.variant = Variant::XoChip;
+        chip.load_rom_bytes(&rom).unwrap();
+        let mut debugger = Debugger::new(chip);
+        debugger.add_watchpoint(0x300, 4);
+        assert_eq!(debugger.cont(10), Stop::Watchpoint { addr: 0x300, pc: 0x202 });
+    }
+
+    #[test]
+    fn test_run_frame_pauses() {
+        let mut debugger = debugger();
+        debugger.add_breakpoint(0x206);
+
+        assert_eq!(debugger.run_frame(), Stop::Breakpoint(0x206));
+        assert!(debugger.is_paused());
+
+        let pc = debugger.chip.pc;
+        assert_eq!(debugger.run_frame(), Stop::Done);
+        assert_eq!(debugger.chip.pc, pc);
+
+        debugger.resume();
+        debugger.remove_breakpoint(0x206);
+        debugger.run_frame();
+        assert!(debugger.chip.pc != pc);
+    }
+
+    #[test]
+    fn test_timers_tick_while_stepping() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x12, 0x00]).unwrap();
+        chip.speed = 600;
+        chip.delay_timer = 5;
+        let mut debugger = Debugger::new(chip);
+
+        for _ in 0..25 {
+            debugger.step();
+        }
+
+        assert_eq!(debugger.chip.delay_timer, 3);
+    }
+
+    #[test]
+    fn test_stepping_keeps_run_frame_pace() {
+        // ADD V0, 1 over and over, 700 ips runs 11 or 12 of them a frame
+        let rom: Vec<u8> = [0x70, 0x01].iter().cycle().take(400).cloned().collect();
+        let machine = || {
+            let mut chip = Chip8::new();
+            chip.load_rom_bytes(&rom).unwrap();
+            chip.speed = 700;
+            chip.delay_timer = 10;
+            chip
+        };
+
+        let mut chip = machine();
+        for _ in 0..6 {
+            chip.run_frame().unwrap();
+        }
+        assert_eq!(chip.registers[0x0], 70);
+        assert_eq!(chip.delay_timer, 4);
+
+        let mut debugger = Debugger::new(machine());
+        for _ in 0..69 {
+            debugger.step();
+        }
+        assert_eq!(debugger.chip.delay_timer, 5);
+        debugger.step();
+        assert_eq!(debugger.chip.registers, chip.registers);
+        assert_eq!(debugger.chip.delay_timer, 4);
+    }
+
+    #[test]
+    fn test_run_frame_waits_for_vblank() {
+        // DRW V0, V0, 1 then JP 200
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0xD0, 0x01, 0x12, 0x00]).unwrap();
+        chip.quirks.display_wait = true;
+        chip.delay_timer = 10;
+        let mut debugger = Debugger::new(chip);
+
+        assert_eq!(debugger.run_frame(), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x202);
+        assert_eq!(debugger.chip.delay_timer, 9);
+
+        // the jump and the next sprite
+        assert_eq!(debugger.run_frame(), Stop::Done);
+        assert_eq!(debugger.chip.pc, 0x202);
+        assert_eq!(debugger.chip.delay_timer, 8);
+    }
+
+    #[test]
+    fn test_parse_condition() {
+        assert_eq!(
+            "vA>=0x20".parse(),
+            Ok(Condition {
+                register: 0xA,
+                op: CompareOp::Ge,
+                value: 0x20
+            })
+        );
+        assert_eq!("V1 < 3".parse::<Condition>().unwrap().op, CompareOp::Lt);
+        assert_eq!("VF != 0".parse::<Condition>().unwrap().op, CompareOp::Ne);
+        assert!("V0 = 1".parse::<Condition>().is_err());
+        assert!("VG == 1".parse::<Condition>().is_err());
+        assert!("V0 == 256".parse::<Condition>().is_err());
+    }
+}
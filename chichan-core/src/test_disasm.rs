@@ -0,0 +1,177 @@
+// Tests
+#[cfg(test)]
+mod test_disasm {
+    use super::super::chip8::Variant;
+    use super::super::disasm::{self, Flow, Listing};
+    use std::collections::BTreeMap;
+
+    fn text(bytes: &[u8]) -> String {
+        disasm::decode(bytes, Variant::XoChip).unwrap().render(&BTreeMap::new())
+    }
+
+    #[test]
+    fn test_decode_mnemonics() {
+        assert_eq!(text(&[0x00, 0xE0]), "CLS");
+        assert_eq!(text(&[0x00, 0xEE]), "RET");
+        assert_eq!(text(&[0x12, 0x34]), "JMP 0x234");
+        assert_eq!(text(&[0x2A, 0xBC]), "CALL 0xABC");
+        assert_eq!(text(&[0x3A, 0x07]), "SE VA, 0x07");
+        assert_eq!(text(&[0x41, 0xFF]), "SNE V1, 0xFF");
+        assert_eq!(text(&[0x51, 0x20]), "SE V1, V2");
+        assert_eq!(text(&[0x6F, 0x10]), "LD VF, 0x10");
+        assert_eq!(text(&[0x83, 0x44]), "ADD V3, V4");
+        assert_eq!(text(&[0x83, 0x46]), "SHR V3, V4");
+        assert_eq!(text(&[0x83, 0x4E]), "SHL V3, V4");
+        assert_eq!(text(&[0x9E, 0xD0]), "SNE VE, VD");
+        assert_eq!(text(&[0xA2, 0xB4]), "LD I, 0x2B4");
+        assert_eq!(text(&[0xB3, 0x00]), "JP V0, 0x300");
+        assert_eq!(text(&[0xC0, 0x0F]), "RND V0, 0x0F");
+        assert_eq!(text(&[0xD0, 0x15]), "DRW V0, V1, 5");
+        assert_eq!(text(&[0xE2, 0x9E]), "SKP V2");
+        assert_eq!(text(&[0xE2, 0xA1]), "SKNP V2");
+        assert_eq!(text(&[0xF4, 0x07]), "LD V4, DT");
+        assert_eq!(text(&[0xF4, 0x0A]), "LD V4, K");
+        assert_eq!(text(&[0xF4, 0x15]), "LD DT, V4");
+        assert_eq!(text(&[0xF4, 0x18]), "LD ST, V4");
+        assert_eq!(text(&[0xF4, 0x1E]), "ADD I, V4");
+        assert_eq!(text(&[0xF4, 0x29]), "LD F, V4");
+        assert_eq!(text(&[0xF4, 0x33]), "LD B, V4");
+        assert_eq!(text(&[0xF4, 0x55]), "LD [I], V4");
+        assert_eq!(text(&[0xF4, 0x65]), "LD V4, [I]");
+    }
+
+    #[test]
+    fn test_decode_extensions() {
+        assert_eq!(text(&[0x00, 0xC4]), "SCD 4");
+        assert_eq!(text(&[0x00, 0xFB]), "SCR");
+        assert_eq!(text(&[0x00, 0xFC]), "SCL");
+        assert_eq!(text(&[0x00, 0xFD]), "EXIT");
+        assert_eq!(text(&[0x00, 0xFE]), "LOW");
+        assert_eq!(text(&[0x00, 0xFF]), "HIGH");
+        assert_eq!(text(&[0xF1, 0x30]), "LD HF, V1");
+        assert_eq!(text(&[0xF1, 0x75]), "LD R, V1");
+        assert_eq!(text(&[0xF1, 0x85]), "LD V1, R");
+        assert_eq!(text(&[0x52, 0x52]), "SAVE V2 - V5");
+        assert_eq!(text(&[0x55, 0x23]), "LOAD V5 - V2");
+        assert_eq!(text(&[0xF0, 0x00, 0xAB, 0xCD]), "LD I, long 0xABCD");
+        assert_eq!(text(&[0xF3, 0x01]), "PLANE 3");
+        assert_eq!(text(&[0xF0, 0x02]), "AUDIO");
+        assert_eq!(text(&[0xF7, 0x3A]), "PITCH V7");
+
+        assert_eq!(disasm::decode(&[0xF0, 0x00, 0xAB, 0xCD], Variant::XoChip).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_decode_unknown() {
+        assert_eq!(disasm::decode(&[0x01, 0xE0], Variant::XoChip), None);
+        assert_eq!(disasm::decode(&[0x51, 0x21], Variant::XoChip), None);
+        assert_eq!(disasm::decode(&[0x80, 0x08], Variant::XoChip), None);
+        assert_eq!(disasm::decode(&[0xE1, 0x00], Variant::XoChip), None);
+        assert_eq!(disasm::decode(&[0xF0, 0xFF], Variant::XoChip), None);
+        assert_eq!(disasm::decode(&[0xF0, 0x00], Variant::XoChip), None);
+        assert_eq!(disasm::decode(&[0x12], Variant::XoChip), None);
+    }
+
+    #[test]
+    fn test_decode_by_variant() {
+        // what the interpreter would skip as unknown isn't decoded either
+        assert_eq!(disasm::decode(&[0x00, 0xFF], Variant::Chip8), None);
+        assert!(disasm::decode(&[0x00, 0xFF], Variant::SuperChip).is_some());
+        assert_eq!(disasm::decode(&[0xF0, 0x00, 0xAB, 0xCD], Variant::Chip8), None);
+        assert_eq!(disasm::decode(&[0xF0, 0x00, 0xAB, 0xCD], Variant::SuperChip), None);
+        assert_eq!(disasm::decode(&[0x52, 0x52], Variant::SuperChip), None);
+        assert!(disasm::decode(&[0x00, 0xE0], Variant::Chip8).is_some());
+    }
+
+    #[test]
+    fn test_flow() {
+        assert_eq!(disasm::decode(&[0x12, 0x08], Variant::XoChip).unwrap().flow, Flow::Jump(0x208));
+        assert_eq!(disasm::decode(&[0x23, 0x00], Variant::XoChip).unwrap().flow, Flow::Call(0x300));
+        assert_eq!(disasm::decode(&[0x00, 0xEE], Variant::XoChip).unwrap().flow, Flow::Stop);
+        assert_eq!(disasm::decode(&[0x30, 0x00], Variant::XoChip).unwrap().flow, Flow::Skip);
+    }
+
+    #[test]
+    fn test_listing() {
+        let rom = [
+            0xA2, 0x0C, // LD I, data
+            0x22, 0x08, // CALL sub
+            0x12, 0x04, // loop: JMP loop
+            0xFF, 0xFF, // unreachable
+            0xD0, 0x15, // sub: DRW V0, V1, 5
+            0x00, 0xEE, // RET
+            0xF0, 0x90, 0x90, 0x90, 0xF0, // data: sprite
+        ];
+
+        let listing = Listing::new(&rom, Variant::Chip8);
+        assert_eq!(listing.code.len(), 5);
+        assert_eq!(listing.labels.get(&0x204).map(String::as_str), Some("label_204"));
+        assert_eq!(listing.labels.get(&0x208).map(String::as_str), Some("sub_208"));
+        assert_eq!(listing.labels.get(&0x20C).map(String::as_str), Some("data_20C"));
+
+        let expected = [
+            "    LD I, data_20C          ; 0x200  A20C",
+            "    CALL sub_208            ; 0x202  2208",
+            "label_204:",
+            "    JMP label_204           ; 0x204  1204",
+            "    db 0xFF, 0xFF           ; 0x206",
+            "sub_208:",
+            "    DRW V0, V1, 5           ; 0x208  D015",
+            "    RET                     ; 0x20A  00EE",
+            "data_20C:",
+            "    db 0xF0, 0x90, 0x90, 0x90, 0xF0 ; 0x20C",
+            "",
+        ];
+        assert_eq!(listing.render(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_skips_follow_both_ways() {
+        // SE V0, 0 / JMP self / CLS / JMP self
+        let rom = [0x30, 0x00, 0x12, 0x02, 0x00, 0xE0, 0x12, 0x06];
+        let listing = Listing::new(&rom, Variant::Chip8);
+
+        assert_eq!(listing.code.len(), 4);
+    }
+
+    #[test]
+    fn test_xochip_skips_long_load() {
+        // SE V0, 0 / LD I, long 0x1234 / JMP self
+        let rom = [0x30, 0x00, 0xF0, 0x00, 0x12, 0x34, 0x12, 0x06];
+
+        let listing = Listing::new(&rom, Variant::XoChip);
+        assert!(listing.code.contains_key(&0x206));
+        assert!(!listing.code.contains_key(&0x204));
+
+        // without XO-CHIP the skip lands inside the long load, which can't be listed
+        let listing = Listing::new(&rom, Variant::Chip8);
+        assert!(listing.code.contains_key(&0x204));
+        assert!(!listing.code.contains_key(&0x202));
+    }
+
+    #[test]
+    fn test_listing_follows_the_variant() {
+        // LD I, long 0x1204 / JMP self
+        let rom = [0xF0, 0x00, 0x12, 0x04, 0x12, 0x04];
+
+        let listing = Listing::new(&rom, Variant::XoChip);
+        assert_eq!(listing.code.len(), 2);
+        assert_eq!(listing.code[&0x200].len(), 4);
+
+        // CHIP-8 stops on F000 like the interpreter, it doesn't swallow the jump after it
+        let listing = Listing::new(&rom, Variant::Chip8);
+        assert!(listing.code.is_empty());
+        assert!(!listing.render().contains("long"));
+    }
+
+    #[test]
+    fn test_roms_disassemble() {
+        for rom in [&include_bytes!("tetris.ch8")[..], &include_bytes!("BC_test.ch8")[..], &include_bytes!("test_opcode.ch8")[..]].iter() {
+            let listing = Listing::new(rom, Variant::Chip8);
+            assert!(!listing.code.is_empty());
+
+            let text = listing.render();
+            assert!(text.lines().count() > 10);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// Tests
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test_draw {
+    use super::super::chip8::Chip8;
+    use super::super::quirks::EdgeMode;
+
+    const SPRITE_ADDRESS: usize = 0x300;
+    // lopsided so a sprite shifted or flipped by a pixel doesn't match
+    const SPRITE: [u8; 5] = [0b1100_0001, 0b1000_0000, 0b0000_0000, 0b0010_0100, 0b1111_1111];
+
+    fn machine(edge_mode: EdgeMode, hires: bool, sprite: &[u8]) -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.quirks.edge_mode = edge_mode;
+        if hires {
+            chip.OP_00FF().unwrap();
+        }
+        chip.memory[SPRITE_ADDRESS..SPRITE_ADDRESS + sprite.len()].copy_from_slice(sprite);
+        chip
+    }
+
+    /// `D01n` with V0 = x and V1 = y, returns VF
+    fn draw(chip: &mut Chip8, x: u8, y: u8, n: u16) -> u8 {
+        chip.index = SPRITE_ADDRESS as u16;
+        chip.registers[0x0] = x;
+        chip.registers[0x1] = y;
+        chip.opcode = 0xD010 | n;
+        chip.OP_Dxyn().unwrap();
+        chip.registers[0xF]
+    }
+
+    fn lit(chip: &Chip8) -> Vec<(usize, usize)> {
+        let mut pixels = Vec::new();
+        for y in 0..chip.height() {
+            for x in 0..chip.width() {
+                if chip.color_index(x, y) != 0 {
+                    pixels.push((x, y));
+                }
+            }
+        }
+        pixels
+    }
+
+    /// Where the set bits of `sprite` (`cols` pixels wide) should land when drawn at (x, y)
+    fn expected(chip: &Chip8, sprite: &[u8], cols: usize, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let (width, height) = (chip.width(), chip.height());
+        let bytes_per_row = cols / 8;
+        let mut pixels = Vec::new();
+
+        for (row, bytes) in sprite.chunks(bytes_per_row).enumerate() {
+            for col in 0..cols {
+                if bytes[col / 8] & (0x80 >> (col % 8)) == 0 {
+                    continue;
+                }
+                let (px, py) = (x % width + col, y % height + row);
+                match chip.quirks.edge_mode {
+                    EdgeMode::Wrap => pixels.push((px % width, py % height)),
+                    EdgeMode::Clip if px < width && py < height => pixels.push((px, py)),
+                    EdgeMode::Clip => {}
+                }
+            }
+        }
+
+        pixels.sort_by_key(|&(x, y)| (y, x));
+        pixels
+    }
+
+    /// Draw the sprite at every position on screen, check where it lands, then draw it again to erase it
+    fn check_every_position(edge_mode: EdgeMode, hires: bool) {
+        let mut chip = machine(edge_mode, hires, &SPRITE);
+
+        for y in 0..chip.height() {
+            for x in 0..chip.width() {
+                assert_eq!(draw(&mut chip, x as u8, y as u8, 5), 0, "collision drawing at ({}, {})", x, y);
+                assert_eq!(lit(&chip), expected(&chip, &SPRITE, 8, x, y), "drawn at ({}, {})", x, y);
+
+                assert_eq!(draw(&mut chip, x as u8, y as u8, 5), 1, "no collision erasing at ({}, {})", x, y);
+                assert!(lit(&chip).is_empty(), "left pixels erasing at ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_position_wrap() {
+        check_every_position(EdgeMode::Wrap, false);
+    }
+
+    #[test]
+    fn test_every_position_clip() {
+        check_every_position(EdgeMode::Clip, false);
+    }
+
+    #[test]
+    fn test_every_position_hires_wrap() {
+        check_every_position(EdgeMode::Wrap, true);
+    }
+
+    #[test]
+    fn test_every_position_hires_clip() {
+        check_every_position(EdgeMode::Clip, true);
+    }
+
+    #[test]
+    fn test_start_position_wraps() {
+        // the top left corner wraps in both modes, for every register value
+        for edge_mode in [EdgeMode::Wrap, EdgeMode::Clip].iter() {
+            let mut chip = machine(*edge_mode, false, &SPRITE);
+            for v in 0..=255u8 {
+                draw(&mut chip, v, 0, 5);
+                assert_eq!(lit(&chip), expected(&chip, &SPRITE, 8, v as usize, 0), "{:?} x = {}", edge_mode, v);
+                draw(&mut chip, v, 0, 5);
+
+                draw(&mut chip, 0, v, 5);
+                assert_eq!(lit(&chip), expected(&chip, &SPRITE, 8, 0, v as usize), "{:?} y = {}", edge_mode, v);
+                draw(&mut chip, 0, v, 5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_top_left_pixel() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0x80]);
+
+        draw(&mut chip, 0, 0, 1);
+
+        assert_eq!(lit(&chip), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_right_edge_stays_on_its_row() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0xFF]);
+
+        draw(&mut chip, 60, 5, 1);
+
+        let row: Vec<(usize, usize)> = vec![(0, 5), (1, 5), (2, 5), (3, 5), (60, 5), (61, 5), (62, 5), (63, 5)];
+        assert_eq!(lit(&chip), row);
+    }
+
+    #[test]
+    fn test_corners_wrap() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0xC0, 0xC0]);
+
+        draw(&mut chip, 63, 31, 2);
+
+        assert_eq!(lit(&chip), vec![(0, 0), (63, 0), (0, 31), (63, 31)]);
+    }
+
+    #[test]
+    fn test_corners_clip() {
+        let mut chip = machine(EdgeMode::Clip, false, &[0xC0, 0xC0]);
+
+        draw(&mut chip, 63, 31, 2);
+
+        assert_eq!(lit(&chip), vec![(63, 31)]);
+    }
+
+    #[test]
+    fn test_large_sprite_corners() {
+        let sprite = [0xFF; 32];
+        let mut chip = machine(EdgeMode::Wrap, true, &sprite);
+
+        draw(&mut chip, 120, 60, 0);
+        assert_eq!(lit(&chip), expected(&chip, &sprite, 16, 120, 60));
+        assert_eq!(lit(&chip).len(), 256);
+        assert_eq!(draw(&mut chip, 120, 60, 0), 1);
+        assert!(lit(&chip).is_empty());
+
+        chip.quirks.edge_mode = EdgeMode::Clip;
+        draw(&mut chip, 120, 60, 0);
+        assert_eq!(lit(&chip).len(), 8 * 4);
+    }
+
+    #[test]
+    fn test_collision_needs_a_lit_pixel_turned_off() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0xF0]);
+
+        assert_eq!(draw(&mut chip, 0, 0, 1), 0);
+        // overlapping only the unset bits of the sprite leaves the lit pixels alone
+        assert_eq!(draw(&mut chip, 4, 0, 1), 0);
+        assert_eq!(draw(&mut chip, 2, 0, 1), 1);
+        assert_eq!(lit(&chip), vec![(0, 0), (1, 0), (6, 0), (7, 0)]);
+    }
+
+    #[test]
+    fn test_collision_through_the_edge() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0xFF]);
+        draw(&mut chip, 0, 0, 1);
+
+        // the wrapped half lands on the first sprite
+        assert_eq!(draw(&mut chip, 60, 0, 1), 1);
+
+        let mut chip = machine(EdgeMode::Clip, false, &[0xFF]);
+        draw(&mut chip, 0, 0, 1);
+
+        // the clipped half is never drawn, so it can't collide
+        assert_eq!(draw(&mut chip, 60, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_position_read_from_VF() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0x80]);
+        chip.index = SPRITE_ADDRESS as u16;
+        chip.registers[0xF] = 7;
+        chip.opcode = 0xDFF1;
+
+        chip.OP_Dxyn().unwrap();
+
+        assert_eq!(lit(&chip), vec![(7, 7)]);
+        assert_eq!(chip.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_collision_in_either_plane() {
+        let mut chip = machine(EdgeMode::Wrap, false, &[0x80, 0x80]);
+        chip.planes = 0b10;
+        draw(&mut chip, 0, 0, 1);
+
+        // plane 1 is empty, plane 2 collides
+        chip.planes = 0b11;
+        assert_eq!(draw(&mut chip, 0, 0, 1), 1);
+        assert_eq!(chip.color_index(0, 0), 1);
+    }
+}
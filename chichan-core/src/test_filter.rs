@@ -0,0 +1,259 @@
+// Tests
+#[cfg(test)]
+mod test_filter {
+    use super::super::asm;
+    use super::super::chip8::Chip8;
+    use super::super::filter::{Filter, Renderer, DEFAULT_DECAY, DEFAULT_FRAMES};
+    use super::super::framebuffer::Framebuffer;
+    use super::super::headless;
+    use super::super::palette::Palette;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 0xFF];
+    const WHITE: [u8; 4] = [255, 255, 255, 0xFF];
+
+    fn pixel(renderer: &Renderer, x: usize, y: usize) -> [u8; 4] {
+        let at = (y * renderer.size().0 + x) * 4;
+        let mut rgba = [0; 4];
+        rgba.copy_from_slice(&renderer.rgba()[at..at + 4]);
+        rgba
+    }
+
+    fn phosphor(decay: f32, frames: usize) -> Filter {
+        Filter::Phosphor { decay, frames }
+    }
+
+    /// A box drawn on one frame and erased on the next, over and over
+    fn flickering() -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.quirks.display_wait = true;
+        let rom = asm::assemble(
+            "
+                LD I, box
+            loop:
+                DRW V0, V0, 1
+                JMP loop
+            box:
+                db 0x80
+            ",
+        )
+        .unwrap();
+        chip.load_rom_bytes(&rom).unwrap();
+        chip
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("off".parse(), Ok(Filter::Off));
+        assert_eq!("merge".parse(), Ok(Filter::Merge));
+        assert_eq!("phosphor".parse(), Ok(phosphor(DEFAULT_DECAY, DEFAULT_FRAMES)));
+        assert_eq!("phosphor:0.25".parse(), Ok(phosphor(0.25, DEFAULT_FRAMES)));
+        assert_eq!("phosphor:0.25:3".parse(), Ok(phosphor(0.25, 3)));
+        assert!("phosphor:1".parse::<Filter>().is_err());
+        assert!("phosphor:bright".parse::<Filter>().is_err());
+        assert!("phosphor:0.5:0".parse::<Filter>().is_err());
+        assert!("phosphor:0.5:61".parse::<Filter>().is_err());
+        assert!("phosphor:0.5:4:2".parse::<Filter>().is_err());
+        assert!("merge:2".parse::<Filter>().is_err());
+        assert_eq!(
+            "crt".parse::<Filter>(),
+            Err(String::from(
+                "unknown filter 'crt' (expected off, merge, phosphor, phosphor:<decay> or phosphor:<decay>:<frames>)"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_off_is_the_framebuffer() {
+        let mut video = Framebuffer::new();
+        video.set(0, 3, 4, true);
+        video.set(1, 5, 6, true);
+        let mut renderer = Renderer::new(Filter::Off, Palette::AMBER);
+
+        assert!(renderer.render(&video));
+        assert_eq!(renderer.size(), (64, 32));
+        assert_eq!(renderer.rgba(), &video.to_rgba(&Palette::AMBER)[..]);
+
+        // nothing dirty, nothing painted
+        video.clear_dirty();
+        assert!(!renderer.render(&video));
+
+        video.set(0, 3, 4, false);
+        assert!(renderer.render(&video));
+        assert_eq!(renderer.rgba(), &video.to_rgba(&Palette::AMBER)[..]);
+    }
+
+    #[test]
+    fn test_palette_change_repaints() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(Filter::Off, Palette::CLASSIC);
+        renderer.render(&video);
+        video.clear_dirty();
+
+        renderer.set_palette(Palette::LCD);
+        assert!(renderer.render(&video));
+        assert_eq!(renderer.rgba(), &video.to_rgba(&Palette::LCD)[..]);
+    }
+
+    #[test]
+    fn test_merge_keeps_the_previous_frame() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(Filter::Merge, Palette::CLASSIC);
+        video.set(0, 1, 1, true);
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), WHITE);
+
+        video.set(0, 1, 1, false);
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), WHITE);
+
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), BLACK);
+    }
+
+    #[test]
+    fn test_merge_keeps_xochip_colors_apart() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(Filter::Merge, Palette::CLASSIC);
+        video.set(0, 1, 1, true);
+        renderer.render(&video);
+
+        // plane 1 only now, the previous frame had plane 0 only: no frame had both
+        video.set(0, 1, 1, false);
+        video.set(1, 1, 1, true);
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), [255, 102, 0, 0xFF]);
+
+        // gone now, so the color it was last lit in
+        video.set(1, 1, 1, false);
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), [255, 102, 0, 0xFF]);
+    }
+
+    #[test]
+    fn test_phosphor_fades_out() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(phosphor(0.5, DEFAULT_FRAMES), Palette::CLASSIC);
+        video.set(0, 1, 1, true);
+        renderer.render(&video);
+
+        video.set(0, 1, 1, false);
+        let mut levels = Vec::new();
+        for _ in 0..10 {
+            renderer.render(&video);
+            levels.push(pixel(&renderer, 1, 1)[0]);
+        }
+        assert_eq!(levels, [127, 63, 31, 15, 7, 3, 1, 0, 0, 0]);
+        assert_eq!(pixel(&renderer, 1, 1)[3], 0xFF);
+
+        // lit again, at full strength straight away
+        video.set(0, 1, 1, true);
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), WHITE);
+    }
+
+    #[test]
+    fn test_phosphor_blends_the_last_frames() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(phosphor(0.5, 3), Palette::CLASSIC);
+        video.set(0, 1, 1, true);
+        renderer.render(&video);
+
+        video.set(0, 1, 1, false);
+        let mut levels = Vec::new();
+        for _ in 0..4 {
+            renderer.render(&video);
+            levels.push(pixel(&renderer, 1, 1)[0]);
+        }
+        // out of the last 3 frames, out of the picture
+        assert_eq!(levels, [127, 63, 0, 0]);
+    }
+
+    #[test]
+    fn test_phosphor_without_frames_shows_the_current_one() {
+        for frames in [0, 1] {
+            let mut video = Framebuffer::new();
+            let mut renderer = Renderer::new(phosphor(0.5, frames), Palette::CLASSIC);
+            video.set(0, 1, 1, true);
+            renderer.render(&video);
+            assert_eq!(pixel(&renderer, 1, 1), WHITE);
+
+            video.set(0, 1, 1, false);
+            renderer.render(&video);
+            assert_eq!(pixel(&renderer, 1, 1)[0], 0);
+        }
+    }
+
+    #[test]
+    fn test_phosphor_fades_in_the_last_color() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(phosphor(0.5, DEFAULT_FRAMES), Palette::CLASSIC);
+        video.set(1, 1, 1, true);
+        renderer.render(&video);
+
+        video.set(1, 1, 1, false);
+        renderer.render(&video);
+        assert_eq!(pixel(&renderer, 1, 1), [127, 51, 0, 0xFF]);
+    }
+
+    #[test]
+    fn test_palette_change_keeps_the_ghosts() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(phosphor(0.5, DEFAULT_FRAMES), Palette::CLASSIC);
+        video.set(0, 1, 1, true);
+        renderer.render(&video);
+
+        video.set(0, 1, 1, false);
+        renderer.set_palette(Palette::AMBER);
+        renderer.render(&video);
+
+        // half way between amber's background and foreground
+        assert_eq!(pixel(&renderer, 1, 1), [140, 94, 0, 0xFF]);
+    }
+
+    #[test]
+    fn test_phosphor_fades_towards_light_backgrounds() {
+        let mut video = Framebuffer::new();
+        let mut renderer = Renderer::new(phosphor(0.5, DEFAULT_FRAMES), Palette::LCD);
+        video.set(0, 0, 0, true);
+        renderer.render(&video);
+
+        video.set(0, 0, 0, false);
+        for _ in 0..10 {
+            renderer.render(&video);
+        }
+
+        let [r, g, b] = Palette::LCD.background;
+        assert_eq!(pixel(&renderer, 0, 0), [r, g, b, 0xFF]);
+    }
+
+    #[test]
+    fn test_mode_switch_starts_over() {
+        let mut chip = Chip8::new();
+        let mut renderer = Renderer::new(phosphor(0.9, DEFAULT_FRAMES), Palette::CLASSIC);
+        chip.video.set(0, 0, 0, true);
+        renderer.render(&chip.video);
+
+        chip.OP_00FF().unwrap();
+        renderer.render(&chip.video);
+
+        assert_eq!(renderer.size(), (128, 64));
+        assert_eq!(renderer.rgba(), &chip.video.to_rgba(&Palette::CLASSIC)[..]);
+    }
+
+    #[test]
+    fn test_headless_flicker() {
+        // every other frame the box is gone without a filter
+        let lit_after = |filter: Filter, frames: u32| {
+            let mut chip = flickering();
+            let mut renderer = Renderer::new(filter, Palette::CLASSIC);
+            headless::run_frames_with(&mut chip, &mut renderer, frames);
+            pixel(&renderer, 0, 0) != BLACK
+        };
+
+        assert_ne!(lit_after(Filter::Off, 10), lit_after(Filter::Off, 11));
+        assert!(lit_after(Filter::Merge, 10));
+        assert!(lit_after(Filter::Merge, 11));
+        assert!(lit_after(phosphor(0.5, DEFAULT_FRAMES), 10));
+        assert!(lit_after(phosphor(0.5, DEFAULT_FRAMES), 11));
+    }
+}
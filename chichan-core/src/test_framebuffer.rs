@@ -0,0 +1,174 @@
+// Tests
+#[cfg(test)]
+mod test_framebuffer {
+    use super::super::chip8::Chip8;
+    use super::super::framebuffer::{self, Framebuffer};
+    use super::super::frontend;
+    use super::super::palette::Palette;
+
+    fn lit(video: &Framebuffer) -> Vec<(usize, usize)> {
+        let mut pixels = Vec::new();
+        for (y, row) in video.rows().enumerate() {
+            for (x, color) in row.enumerate() {
+                if color != 0 {
+                    pixels.push((x, y));
+                }
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut video = Framebuffer::new();
+        video.set(0, 3, 1, true);
+        video.set(1, 3, 1, true);
+        video.set(1, 63, 31, true);
+
+        assert_eq!(video.get(3, 1), 3);
+        assert_eq!(video.get(63, 31), 2);
+        assert_eq!(video.get(0, 0), 0);
+        assert_eq!(video.row_bits(0, 1), 1 << (127 - 3));
+
+        video.set(0, 3, 1, false);
+        assert_eq!(video.get(3, 1), 2);
+    }
+
+    #[test]
+    fn test_modes() {
+        let mut video = Framebuffer::new();
+        assert_eq!((video.width(), video.height()), (framebuffer::WIDTH, framebuffer::HEIGHT));
+        video.set(0, 1, 1, true);
+
+        video.set_hires(true);
+        assert!(video.hires());
+        assert_eq!((video.width(), video.height()), (128, 64));
+        assert!(lit(&video).is_empty());
+
+        video.set(0, 127, 63, true);
+        assert_eq!(lit(&video), vec![(127, 63)]);
+        video.set_hires(false);
+        assert!(lit(&video).is_empty());
+    }
+
+    #[test]
+    fn test_xor_row() {
+        let mut video = Framebuffer::new();
+
+        assert!(!video.xor_row(0, 2, 0b11 << 120));
+        assert!(video.xor_row(0, 2, 0b110 << 120));
+        assert_eq!(lit(&video), vec![(5, 2), (7, 2)]);
+
+        // lores rows are 64 pixels, the rest of the word isn't on screen
+        assert!(!video.xor_row(0, 2, 1));
+        assert_eq!(video.row_bits(0, 2), 0b101 << 120);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut video = Framebuffer::new();
+        video.set(0, 0, 0, true);
+        video.set(0, 62, 1, true);
+        video.set(1, 10, 10, true);
+
+        video.scroll_right(0b01, 4);
+        assert_eq!(lit(&video), vec![(4, 0), (10, 10)]);
+
+        video.scroll_left(0b01, 2);
+        assert_eq!(lit(&video), vec![(2, 0), (10, 10)]);
+
+        video.scroll_down(0b11, 25);
+        assert_eq!(lit(&video), vec![(2, 25)]);
+
+        video.scroll_down(0b11, 7);
+        assert!(lit(&video).is_empty());
+    }
+
+    #[test]
+    fn test_clear_selected_planes() {
+        let mut video = Framebuffer::new();
+        video.set(0, 1, 1, true);
+        video.set(1, 2, 2, true);
+
+        video.clear(0b10);
+        assert_eq!(lit(&video), vec![(1, 1)]);
+        video.clear(0b11);
+        assert!(lit(&video).is_empty());
+    }
+
+    #[test]
+    fn test_dirty_rows() {
+        let mut video = Framebuffer::new();
+        assert!(video.is_dirty());
+        video.clear_dirty();
+        assert!(!video.is_dirty());
+
+        video.set(0, 5, 3, true);
+        video.xor_row(1, 20, 1 << 127);
+        // nothing to draw, nothing changes
+        video.xor_row(0, 7, 0);
+        assert_eq!(video.dirty_rows().collect::<Vec<_>>(), vec![3, 20]);
+        assert!(video.is_row_dirty(3));
+        assert!(!video.is_row_dirty(7));
+
+        video.clear_dirty();
+        video.scroll_right(0b01, 4);
+        assert_eq!(video.dirty_rows().count(), 32);
+
+        video.clear_dirty();
+        video.set_hires(true);
+        assert_eq!(video.dirty_rows().count(), 64);
+    }
+
+    #[test]
+    fn test_run_frame_clears_dirty_rows() {
+        let mut chip = Chip8::new();
+        // draw the 0 glyph at (0, 0), then loop
+        chip.load_rom_bytes(&[0xA0, 0x50, 0xD0, 0x05, 0x12, 0x04]).unwrap();
+        chip.video.clear_dirty();
+        chip.quirks.display_wait = true;
+
+        chip.cycle().unwrap();
+        chip.cycle().unwrap();
+        assert_eq!(chip.video.dirty_rows().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+        frontend::run_frame(&mut chip, &mut ()).unwrap();
+        assert!(!chip.video.is_dirty());
+    }
+
+    #[test]
+    fn test_rgba() {
+        let mut video = Framebuffer::new();
+        video.set(0, 1, 0, true);
+        video.set(1, 0, 1, true);
+
+        let rgba = video.to_rgba(&Palette::CLASSIC);
+
+        assert_eq!(rgba.len(), 64 * 32 * 4);
+        assert_eq!(&rgba[..4], &[0, 0, 0, 0xFF]);
+        assert_eq!(&rgba[4..8], &[255, 255, 255, 0xFF]);
+        assert_eq!(&rgba[64 * 4..64 * 4 + 4], &[255, 102, 0, 0xFF]);
+    }
+
+    #[test]
+    fn test_equality_ignores_dirty_rows() {
+        let mut a = Framebuffer::new();
+        let b = Framebuffer::new();
+        a.clear_dirty();
+        assert_eq!(a, b);
+
+        a.set(0, 0, 0, true);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_save_state_only_stores_the_screen() {
+        let mut chip = Chip8::new();
+        let lores = chip.save_state().len();
+        chip.video.set_hires(true);
+        let hires = chip.save_state().len();
+
+        // two planes of one bit per pixel
+        assert_eq!(hires - lores, 2 * (128 * 64 - 64 * 32) / 8);
+    }
+}
@@ -0,0 +1,70 @@
+// Tests
+#[cfg(test)]
+mod test_frontend {
+    use super::super::asm;
+    use super::super::chip8::{Chip8, Chip8Error, StepEvent};
+    use super::super::frontend::{self, Frontend};
+
+    #[derive(Default)]
+    struct Recorder {
+        keys: [u8; 16],
+        frames_drawn: u32,
+        beeping: Vec<bool>,
+    }
+
+    impl Frontend for Recorder {
+        fn read_keys(&mut self, keypad: &mut [u8; 16]) {
+            *keypad = self.keys;
+        }
+
+        fn draw(&mut self, _chip: &Chip8) {
+            self.frames_drawn += 1;
+        }
+
+        fn set_sound(&mut self, _chip: &Chip8, beeping: bool) {
+            self.beeping.push(beeping);
+        }
+    }
+
+    fn chip(source: &str) -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&asm::assemble(source).unwrap()).unwrap();
+        chip
+    }
+
+    #[test]
+    fn test_run_frame_reads_keys() {
+        // V1 = the key pressed, then spin
+        let mut chip = chip("LD V1, K\nend: JMP end");
+        let mut recorder = Recorder::default();
+
+        assert_eq!(frontend::run_frame(&mut chip, &mut recorder), Ok(StepEvent::WaitingForKey));
+        recorder.keys[0x7] = 1;
+        frontend::run_frame(&mut chip, &mut recorder).unwrap();
+
+        assert_eq!(chip.registers[0x1], 0x7);
+        assert_eq!(chip.keypad[0x7], 1);
+        assert_eq!(recorder.frames_drawn, 2);
+    }
+
+    #[test]
+    fn test_run_frame_sound() {
+        // 2 frames of sound
+        let mut chip = chip("LD V0, 2\nLD ST, V0\nend: JMP end");
+        let mut recorder = Recorder::default();
+
+        for _ in 0..3 {
+            frontend::run_frame(&mut chip, &mut recorder).unwrap();
+        }
+        assert_eq!(recorder.beeping, [true, false, false]);
+
+        let mut faulting = self::chip("LD V0, 2\nLD ST, V0\nRET");
+        let mut recorder = Recorder::default();
+        assert_eq!(
+            frontend::run_frame(&mut faulting, &mut recorder),
+            Err(Chip8Error::StackUnderflow)
+        );
+        assert_eq!(recorder.beeping, [false]);
+        assert_eq!(recorder.frames_drawn, 1);
+    }
+}
@@ -0,0 +1,108 @@
+// Tests
+#[cfg(test)]
+mod test_golden {
+    use super::super::asm;
+    use super::super::chip8::Chip8;
+    use super::super::headless::{self, KeyScript, Outcome};
+    use std::env;
+    use std::fs;
+
+    /// Run `frames` frames, applying `keys` as it goes, and compare the screen with `src/golden/<name>.txt`.
+    /// With `CHICHAN_BLESS=1` a missing or different golden is (re)written instead
+    fn check_golden(name: &str, rom: &[u8], frames: u32, keys: &[(u32, usize, bool)]) {
+        let mut chip = Chip8::new();
+        // fixed seed so roms using Cxkk draw the same every run
+        chip.seed(1);
+        chip.load_rom_bytes(rom).unwrap();
+
+        match headless::run_frames_with(&mut chip, &mut KeyScript::new(keys), frames) {
+            (_, Outcome::Finished) => {}
+            (frame, outcome) => panic!("{} {} at frame {}", name, outcome, frame),
+        }
+
+        let actual = format!("{}\n", headless::screen_ascii(&chip));
+        let path = format!("{}/src/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+        let bless = env::var("CHICHAN_BLESS").is_ok_and(|value| value == "1");
+
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(_) | Err(_) if bless => fs::write(&path, &actual).unwrap(),
+            Ok(expected) => panic!(
+                "{} doesn't match {}, rerun with CHICHAN_BLESS=1 if the change is intended\n{}",
+                name,
+                path,
+                diff(&expected, &actual)
+            ),
+            Err(err) => panic!("{}: {}, run with CHICHAN_BLESS=1 to create it", path, err),
+        }
+    }
+
+    /// Golden and actual screens side by side, `X` marking the pixels that differ and `>` their rows
+    fn diff(expected: &str, actual: &str) -> String {
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+        let width = expected.iter().chain(actual.iter()).map(|line| line.len()).max().unwrap_or(0);
+
+        let mut lines = vec![format!("  {:<w$} | {:<w$} | diff", "golden", "actual", w = width)];
+        for row in 0..expected.len().max(actual.len()) {
+            let golden = expected.get(row).copied().unwrap_or("");
+            let got = actual.get(row).copied().unwrap_or("");
+            let marks: String = (0..width)
+                .map(|x| if golden.as_bytes().get(x) == got.as_bytes().get(x) { ' ' } else { 'X' })
+                .collect();
+            let changed = if marks.trim().is_empty() { ' ' } else { '>' };
+            lines.push(format!("{} {:<w$} | {:<w$} | {}", changed, golden, got, marks.trim_end(), w = width));
+        }
+
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_diff_marks_changed_pixels() {
+        let diff = diff("#.\n..\n", "#.\n.#\n");
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[1], "  #. | #. | ");
+        assert_eq!(lines[2], "> .. | .# |  X");
+    }
+
+    #[test]
+    fn test_golden_bc_test() {
+        check_golden("BC_test", include_bytes!("BC_test.ch8"), 300, &[]);
+    }
+
+    #[test]
+    fn test_golden_test_opcode() {
+        check_golden("test_opcode", include_bytes!("test_opcode.ch8"), 300, &[]);
+    }
+
+    #[test]
+    fn test_golden_tetris() {
+        // move the first piece right, rotate it and drop the second one
+        let keys = [(60, 0x6, true), (64, 0x6, false), (80, 0x4, true), (84, 0x4, false), (200, 0x7, true), (240, 0x7, false)];
+        check_golden("tetris", include_bytes!("tetris.ch8"), 400, &keys);
+    }
+
+    #[test]
+    fn test_golden_scripted_keys() {
+        // draws the digit of every key pressed, one after the other
+        let rom = asm::assemble(
+            "
+                LD V1, 8
+                LD V2, 8
+            loop:
+                LD V0, K
+                LD F, V0
+                DRW V1, V2, 5
+                ADD V1, 5
+            release:
+                SKNP V0
+                JMP release
+                JMP loop
+            ",
+        )
+        .unwrap();
+        let keys = [(5, 0xA, true), (10, 0xA, false), (20, 0x3, true), (25, 0x3, false), (30, 0x7, true), (40, 0x7, false)];
+
+        check_golden("scripted_keys", &rom, 60, &keys);
+    }
+}
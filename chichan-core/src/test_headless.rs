@@ -0,0 +1,57 @@
+// Tests
+#[cfg(test)]
+mod test_headless {
+    use super::super::asm;
+    use super::super::chip8::{Chip8, Chip8Error, Variant};
+    use super::super::headless::{self, Outcome};
+
+    fn chip(source: &str) -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&asm::assemble(source).unwrap()).unwrap();
+        chip
+    }
+
+    #[test]
+    fn test_run_frames_outcomes() {
+        let mut looping = chip("loop: JMP loop");
+        assert_eq!(headless::run_frames(&mut looping, 30), (30, Outcome::Finished));
+
+        let mut exiting = chip("LD V0, 1\nEXIT");
+        exiting.variant = Variant::SuperChip;
+        assert_eq!(headless::run_frames(&mut exiting, 30), (1, Outcome::Exited));
+
+        let mut faulting = chip("RET");
+        assert_eq!(
+            headless::run_frames(&mut faulting, 30),
+            (1, Outcome::Halted(Chip8Error::StackUnderflow))
+        );
+
+        assert_eq!(Outcome::Finished.exit_code(), 0);
+        assert_eq!(Outcome::Exited.exit_code(), 0);
+        assert_eq!(Outcome::Halted(Chip8Error::StackUnderflow).exit_code(), 1);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut chip = chip("
+                LD V0, 8
+                LD V1, 0
+                LD I, box
+                DRW V0, V1, 1
+            end: JMP end
+            box: db 0xF0
+        ");
+        let (frames, outcome) = headless::run_frames(&mut chip, 10);
+        let report = headless::report(&chip, frames, outcome);
+
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0].len(), 64);
+        assert_eq!(lines[0].matches('#').count(), 4);
+        assert!(lines[1..32].iter().all(|line| !line.contains('#')));
+        assert_eq!(lines[32], "");
+        assert!(lines[33].starts_with("V0=08 V1=00"));
+        assert!(lines[35].starts_with("I=0x20A PC=0x208"));
+        assert_eq!(lines[37], "frames: 10");
+        assert_eq!(lines[38], "status: finished");
+    }
+}
@@ -0,0 +1,73 @@
+// Tests
+#[cfg(test)]
+mod test_palette {
+    use super::super::palette::Palette;
+
+    #[test]
+    fn test_default_is_classic() {
+        assert_eq!(Palette::default(), Palette::CLASSIC);
+        assert_eq!(
+            Palette::CLASSIC.colors(),
+            [[0, 0, 0], [255, 255, 255], [255, 102, 0], [102, 33, 0]]
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("amber".parse(), Ok(Palette::AMBER));
+        assert_eq!("green".parse(), Ok(Palette::GREEN_PHOSPHOR));
+        assert_eq!("lcd".parse(), Ok(Palette::LCD));
+        assert_eq!("colorblind".parse(), Ok(Palette::COLORBLIND));
+        assert_eq!(
+            "sepia".parse::<Palette>(),
+            Err(String::from(
+                "unknown palette 'sepia' (expected classic, amber, green, lcd, contrast, colorblind)"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_presets_have_four_distinct_colors() {
+        // channel differences added up, enough to tell the colors apart at a glance
+        let distance = |a: [u8; 3], b: [u8; 3]| (0..3).map(|i| (a[i] as i32 - b[i] as i32).abs()).sum::<i32>();
+
+        for (name, palette) in Palette::PRESETS.iter() {
+            let colors = palette.colors();
+            for a in 0..4 {
+                for b in a + 1..4 {
+                    assert!(
+                        distance(colors[a], colors[b]) >= 96,
+                        "{} has colors {} and {} too close",
+                        name,
+                        a,
+                        b
+                    );
+                }
+            }
+            assert_eq!(palette.name(), Some(*name));
+        }
+    }
+
+    #[test]
+    fn test_next_cycles_through_the_presets() {
+        let mut palette = Palette::CLASSIC;
+        let mut names = Vec::new();
+        for _ in 0..Palette::PRESETS.len() {
+            palette = palette.next();
+            names.push(palette.name().unwrap());
+        }
+
+        assert_eq!(names, ["amber", "green", "lcd", "contrast", "colorblind", "classic"]);
+    }
+
+    #[test]
+    fn test_custom_palette() {
+        let custom = Palette {
+            background: [1, 2, 3],
+            ..Palette::AMBER
+        };
+
+        assert_eq!(custom.name(), None);
+        assert_eq!(custom.next(), Palette::CLASSIC);
+    }
+}
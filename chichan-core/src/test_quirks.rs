@@ -0,0 +1,177 @@
+// Tests
+#[cfg(test)]
+mod test_quirks {
+    use super::super::chip8::Chip8;
+    use super::super::quirks::{EdgeMode, IndexIncrement, Quirks};
+
+    const START_ADDRESS: usize = 0x200;
+
+    #[test]
+    fn test_profiles() {
+        assert_eq!("vip".parse::<Quirks>().unwrap(), Quirks::COSMAC_VIP);
+        assert_eq!("chip48".parse::<Quirks>().unwrap(), Quirks::CHIP_48);
+        assert_eq!("schip".parse::<Quirks>().unwrap(), Quirks::SUPER_CHIP);
+        assert_eq!("xochip".parse::<Quirks>().unwrap(), Quirks::XO_CHIP);
+        assert!("octo".parse::<Quirks>().is_err());
+    }
+
+    #[test]
+    fn test_presets_differ() {
+        let presets = [Quirks::COSMAC_VIP, Quirks::CHIP_48, Quirks::SUPER_CHIP, Quirks::XO_CHIP];
+        for (i, a) in presets.iter().enumerate() {
+            for b in &presets[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_uses_vy() {
+        let mut chip = Chip8::new();
+        chip.registers[0x1] = 0b0000_0100;
+        chip.registers[0x2] = 0b1000_0011;
+        chip.opcode = 0x8126;
+
+        chip.OP_8xy6().unwrap();
+        assert_eq!(chip.registers[0x1], 0b0000_0010);
+        assert_eq!(chip.registers[0xF], 0);
+
+        chip.quirks.shift_uses_vy = true;
+        chip.OP_8xy6().unwrap();
+        assert_eq!(chip.registers[0x1], 0b0100_0001);
+        assert_eq!(chip.registers[0xF], 1);
+
+        chip.opcode = 0x812E;
+        chip.OP_8xyE().unwrap();
+        assert_eq!(chip.registers[0x1], 0b0000_0110);
+        assert_eq!(chip.registers[0xF], 1);
+    }
+
+    #[test]
+    fn test_shift_into_vf_keeps_the_flag() {
+        for &shift_uses_vy in &[false, true] {
+            let mut chip = Chip8::new();
+            chip.quirks.shift_uses_vy = shift_uses_vy;
+
+            // the shifted value would be 1 and 0x80, the flag is 0 both times
+            chip.registers[0x2] = 0b0000_0010;
+            chip.registers[0xF] = 0b0000_0010;
+            chip.opcode = 0x8F26;
+            chip.OP_8xy6().unwrap();
+            assert_eq!(chip.registers[0xF], 0);
+
+            chip.registers[0x2] = 0b0100_0000;
+            chip.registers[0xF] = 0b0100_0000;
+            chip.opcode = 0x8F2E;
+            chip.OP_8xyE().unwrap();
+            assert_eq!(chip.registers[0xF], 0);
+
+            // and 1 when the shifted out bit is set
+            chip.registers[0x2] = 0b0000_0011;
+            chip.registers[0xF] = 0b0000_0011;
+            chip.opcode = 0x8F26;
+            chip.OP_8xy6().unwrap();
+            assert_eq!(chip.registers[0xF], 1);
+
+            chip.registers[0x2] = 0b1000_0000;
+            chip.registers[0xF] = 0b1000_0000;
+            chip.opcode = 0x8F2E;
+            chip.OP_8xyE().unwrap();
+            assert_eq!(chip.registers[0xF], 1);
+        }
+    }
+
+    #[test]
+    fn test_load_store_index() {
+        let mut chip = Chip8::new();
+        chip.index = 0x300;
+        chip.opcode = 0xF255;
+
+        chip.OP_Fx55().unwrap();
+        assert_eq!(chip.index, 0x300);
+
+        chip.quirks.load_store_index = IndexIncrement::PastLast;
+        chip.OP_Fx55().unwrap();
+        assert_eq!(chip.index, 0x303);
+
+        chip.opcode = 0xF165;
+        chip.OP_Fx65().unwrap();
+        assert_eq!(chip.index, 0x305);
+
+        chip.quirks.load_store_index = IndexIncrement::ByX;
+        chip.OP_Fx65().unwrap();
+        assert_eq!(chip.index, 0x306);
+        chip.opcode = 0xF255;
+        chip.OP_Fx55().unwrap();
+        assert_eq!(chip.index, 0x308);
+    }
+
+    #[test]
+    fn test_jump_uses_vx() {
+        let mut chip = Chip8::new();
+        chip.registers[0x0] = 0x10;
+        chip.registers[0x3] = 0x20;
+        chip.opcode = 0xB300;
+
+        chip.OP_Bnnn().unwrap();
+        assert_eq!(chip.pc, 0x310);
+
+        chip.quirks.jump_uses_vx = true;
+        chip.OP_Bnnn().unwrap();
+        assert_eq!(chip.pc, 0x320);
+    }
+
+    #[test]
+    fn test_vf_reset() {
+        let mut chip = Chip8::new();
+        chip.registers[0xF] = 1;
+        chip.opcode = 0x8121;
+
+        chip.OP_8xy1().unwrap();
+        assert_eq!(chip.registers[0xF], 1);
+
+        chip.quirks.vf_reset = true;
+        chip.OP_8xy1().unwrap();
+        assert_eq!(chip.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_display_wait() {
+        let mut chip = Chip8::new();
+        // DRW V0, V0, 1 | ADD V1, 1 | JP 0x200
+        chip.load_rom_bytes(&[0xD0, 0x01, 0x71, 0x01, 0x12, 0x00]).unwrap();
+        chip.index = 0x50;
+        chip.registers[0x0] = 8;
+        chip.speed = 600;
+
+        chip.run_frame().unwrap();
+        // 10 instructions: DRW, ADD, JP three times then one more DRW
+        assert_eq!(chip.registers[0x1], 3);
+
+        chip.quirks.display_wait = true;
+        chip.pc = START_ADDRESS as u16;
+        chip.registers[0x1] = 0;
+        chip.run_frame().unwrap();
+        assert_eq!(chip.registers[0x1], 0);
+        assert_eq!(chip.pc, START_ADDRESS as u16 + 2);
+    }
+
+    #[test]
+    fn test_edge_mode() {
+        let lit = |edge_mode: EdgeMode| {
+            let mut chip = Chip8::new();
+            chip.quirks.edge_mode = edge_mode;
+            chip.memory[0x300] = 0xFF;
+            chip.index = 0x300;
+            chip.registers[0x0] = 60;
+            chip.registers[0x1] = 4;
+            chip.opcode = 0xD011;
+
+            chip.OP_Dxyn().unwrap();
+            chip.video.rows().flatten().filter(|color| *color != 0).count()
+        };
+
+        assert_eq!(lit(EdgeMode::Wrap), 8);
+        assert_eq!(lit(EdgeMode::Clip), 4);
+    }
+}
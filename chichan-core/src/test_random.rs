@@ -0,0 +1,67 @@
+// Tests
+#[cfg(test)]
+mod test_random {
+    use super::super::chip8::Chip8;
+    use super::super::random::{Random, Scripted, XorShift};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_xorshift_repeats_per_seed() {
+        let bytes = |seed| {
+            let mut rng = XorShift::new(seed);
+            (0..8).map(|_| rng.next_byte()).collect::<Vec<u8>>()
+        };
+
+        assert_eq!(bytes(1234), bytes(1234));
+        assert_ne!(bytes(1234), bytes(4321));
+        // a zero seed doesn't lock the generator at zero
+        assert!(bytes(0).iter().any(|byte| *byte != 0));
+        assert_eq!(bytes(0), bytes(0x2545_F491));
+    }
+
+    #[test]
+    fn test_injected_rng() {
+        let calls = Cell::new(0);
+        let mut chip = Chip8::with_rng(|| {
+            calls.set(calls.get() + 1);
+            0xAB
+        });
+        // Cxkk: Vx = random & kk
+        chip.load_rom_bytes(&[0xC0, 0xFF, 0xC1, 0x0F]).unwrap();
+
+        chip.cycle().unwrap();
+        chip.cycle().unwrap();
+        assert_eq!(calls.get(), 2);
+        assert_eq!(chip.registers[0x0], 0xAB);
+        assert_eq!(chip.registers[0x1], 0x0B);
+    }
+
+    #[test]
+    fn test_scripted_rng() {
+        let mut chip = Chip8::with_rng(Scripted::new(&[0x12, 0xF0]));
+        // V0..V2 = random & 0xFF
+        chip.load_rom_bytes(&[0xC0, 0xFF, 0xC1, 0xFF, 0xC2, 0xFF]).unwrap();
+
+        for _ in 0..3 {
+            chip.cycle().unwrap();
+        }
+        assert_eq!(chip.registers[..3], [0x12, 0xF0, 0x12]);
+    }
+
+    #[test]
+    fn test_seed() {
+        let rom = [0xC0, 0xFF, 0xC1, 0xFF, 0xC2, 0xFF, 0xC3, 0xFF];
+        let run = |seed| {
+            let mut chip = Chip8::new();
+            chip.seed(seed);
+            chip.load_rom_bytes(&rom).unwrap();
+            for _ in 0..4 {
+                chip.cycle().unwrap();
+            }
+            chip.registers
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+}
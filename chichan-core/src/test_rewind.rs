@@ -0,0 +1,174 @@
+// Tests
+#[cfg(test)]
+mod test_rewind {
+    use super::super::chip8::{Chip8, Chip8Error};
+    use super::super::rewind::Rewind;
+
+    // counts V0 up by one per frame, an add and a jump each frame
+    fn counter() -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x70, 0x01, 0x12, 0x00]).unwrap();
+        chip.speed = 120;
+        chip
+    }
+
+    #[test]
+    fn test_rewind_frames() {
+        let mut chip = counter();
+        let mut rewind = Rewind::new(100, usize::MAX);
+        rewind.record(&chip);
+
+        for _ in 0..10 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+        assert_eq!(chip.registers[0x0], 10);
+        assert_eq!(rewind.len(), 10);
+
+        assert_eq!(rewind.rewind(&mut chip, 4), 4);
+        assert_eq!(chip.registers[0x0], 6);
+        assert_eq!(rewind.len(), 6);
+
+        // 0 puts back the newest frame left
+        chip.registers[0x0] = 99;
+        assert_eq!(rewind.rewind(&mut chip, 0), 0);
+        assert_eq!(chip.registers[0x0], 6);
+
+        // only as far back as the first frame recorded
+        assert_eq!(rewind.rewind(&mut chip, 50), 6);
+        assert_eq!(chip.registers[0x0], 0);
+        assert_eq!(chip.pc, 0x200);
+        assert!(rewind.is_empty());
+    }
+
+    #[test]
+    fn test_replay_after_rewind() {
+        let mut chip = counter();
+        let mut rewind = Rewind::new(100, usize::MAX);
+
+        for _ in 0..6 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+        let expected = chip.save_state();
+
+        rewind.rewind(&mut chip, 3);
+        for _ in 0..3 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+
+        assert_eq!(chip.save_state(), expected);
+        assert_eq!(rewind.len(), 5);
+    }
+
+    #[test]
+    fn test_rewind_before_crash() {
+        // RET with an empty stack once V0 reaches 3, on the third frame
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x70, 0x01, 0x30, 0x03, 0x12, 0x00, 0x00, 0xEE]).unwrap();
+        chip.speed = 60 * 3;
+        let mut rewind = Rewind::new(100, usize::MAX);
+
+        let err = loop {
+            match chip.run_frame() {
+                Ok(_) => rewind.record(&chip),
+                Err(err) => break err,
+            }
+        };
+        assert_eq!(err, Chip8Error::StackUnderflow);
+
+        rewind.rewind(&mut chip, 0);
+        assert_eq!(chip.registers[0x0], 2);
+        assert_eq!(chip.pc, 0x200);
+    }
+
+    #[test]
+    fn test_frame_limit() {
+        let mut chip = counter();
+        let mut rewind = Rewind::new(3, usize::MAX);
+
+        for _ in 0..10 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+
+        assert_eq!(rewind.len(), 3);
+        assert_eq!(rewind.rewind(&mut chip, 10), 3);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut chip = counter();
+        let mut rewind = Rewind::with_seconds(0, usize::MAX);
+        assert!(!rewind.is_enabled());
+
+        for _ in 0..10 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+
+        assert!(rewind.is_empty());
+        assert!(Rewind::with_seconds(1, usize::MAX).is_enabled());
+    }
+
+    #[test]
+    fn test_longest_history() {
+        let mut chip = counter();
+        let mut rewind = Rewind::with_seconds(u32::MAX, usize::MAX);
+
+        for _ in 0..10 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+
+        assert_eq!(rewind.len(), 9);
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let mut chip = counter();
+        let mut rewind = Rewind::new(1000, 200);
+
+        for _ in 0..100 {
+            chip.run_frame().unwrap();
+            // dirty some memory so every delta costs something
+            chip.memory[0x300 + chip.registers[0x0] as usize] = 0xFF;
+            rewind.record(&chip);
+        }
+
+        assert!(rewind.len() < 100);
+        assert!(rewind.memory_usage() <= 200 + chip.save_state().len());
+    }
+
+    #[test]
+    fn test_deltas_are_small() {
+        let mut chip = counter();
+        let mut rewind = Rewind::new(1000, usize::MAX);
+        rewind.record(&chip);
+        let full = rewind.memory_usage();
+
+        for _ in 0..60 {
+            chip.run_frame().unwrap();
+            rewind.record(&chip);
+        }
+
+        // a register and the pc change per frame, not the whole 4 KiB
+        assert!(rewind.memory_usage() - full < 60 * 32);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut chip = counter();
+        let mut rewind = Rewind::new(100, usize::MAX);
+        rewind.record(&chip);
+        chip.run_frame().unwrap();
+        rewind.record(&chip);
+
+        rewind.clear();
+
+        assert!(rewind.is_empty());
+        assert_eq!(rewind.memory_usage(), 0);
+        assert_eq!(rewind.rewind(&mut chip, 1), 0);
+    }
+}
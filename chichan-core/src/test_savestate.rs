@@ -0,0 +1,195 @@
+// Tests
+#[cfg(test)]
+mod test_savestate {
+    use super::super::chip8::{Chip8, Variant};
+    use super::super::quirks::Quirks;
+    use super::super::savestate::{StateError, STATE_VERSION};
+
+    fn running_chip() -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x60, 0x2A, 0xA3, 0x00, 0xD0, 0x05, 0x22, 0x0A, 0x12, 0x08, 0x00, 0xEE])
+            .unwrap();
+        chip.quirks = Quirks::CHIP_48;
+        chip.delay_timer = 30;
+        chip.sound_timer = 4;
+        chip.keypad[0x7] = 1;
+        chip.rpl_flags[3] = 9;
+        // stop inside the subroutine so the stack is in use
+        for _ in 0..4 {
+            chip.cycle().unwrap();
+        }
+        chip
+    }
+
+    fn assert_same_machine(a: &Chip8, b: &Chip8) {
+        assert_eq!(&a.memory[..], &b.memory[..]);
+        assert_eq!(a.registers, b.registers);
+        assert_eq!(a.index, b.index);
+        assert_eq!(a.pc, b.pc);
+        assert_eq!(a.stack, b.stack);
+        assert_eq!(a.sp, b.sp);
+        assert_eq!(a.delay_timer, b.delay_timer);
+        assert_eq!(a.sound_timer, b.sound_timer);
+        assert_eq!(a.keypad, b.keypad);
+        assert_eq!(a.video, b.video);
+        assert_eq!(a.planes, b.planes);
+        assert_eq!(a.rpl_flags, b.rpl_flags);
+        assert_eq!(a.audio_pattern, b.audio_pattern);
+        assert_eq!(a.pitch, b.pitch);
+        assert_eq!(a.quirks, b.quirks);
+        assert_eq!(a.variant, b.variant);
+        assert_eq!(a.speed, b.speed);
+        assert_eq!(a.rng, b.rng);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let chip = running_chip();
+        let state = chip.save_state();
+
+        let mut restored = Chip8::new();
+        restored.load_state(&state).unwrap();
+
+        assert_same_machine(&chip, &restored);
+        assert_eq!(restored.sp, 1);
+        assert_eq!(restored.registers[0x0], 0x2A);
+    }
+
+    #[test]
+    fn test_random_sequence_is_restored() {
+        // V0 = random, loop
+        let rom = [0xC0, 0xFF, 0x12, 0x00];
+        let mut chip = Chip8::new();
+        chip.seed(99);
+        chip.load_rom_bytes(&rom).unwrap();
+        let state = chip.save_state();
+
+        let mut first = Vec::new();
+        for _ in 0..8 {
+            chip.cycle().unwrap();
+            first.push(chip.registers[0x0]);
+        }
+
+        // a differently seeded machine picks up the saved sequence
+        let mut restored = Chip8::new();
+        restored.seed(1);
+        restored.load_state(&state).unwrap();
+        let mut second = Vec::new();
+        for _ in 0..8 {
+            restored.cycle().unwrap();
+            second.push(restored.registers[0x0]);
+        }
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_restored_machine_runs_the_same() {
+        let mut chip = running_chip();
+        let mut restored = Chip8::new();
+        restored.load_state(&chip.save_state()).unwrap();
+
+        for _ in 0..20 {
+            assert_eq!(chip.run_frame(), restored.run_frame());
+        }
+        assert_same_machine(&chip, &restored);
+    }
+
+    #[test]
+    fn test_xochip_round_trip() {
+        let mut chip = Chip8::new();
+        chip.variant = Variant::XoChip;
+        chip.memory[0xFFFF] = 0x42;
+        chip.planes = 0b11;
+        chip.video.set_hires(true);
+        chip.video.set(1, 100, 40, true);
+        chip.audio_pattern = Some([0x0F; 16]);
+        chip.pitch = 80;
+
+        let mut restored = Chip8::new();
+        restored.load_state(&chip.save_state()).unwrap();
+
+        assert_same_machine(&chip, &restored);
+    }
+
+    #[test]
+    fn test_header() {
+        let state = Chip8::new().save_state();
+
+        assert_eq!(&state[..4], b"CHST");
+        assert_eq!(u16::from_le_bytes([state[4], state[5]]), STATE_VERSION);
+    }
+
+    #[test]
+    fn test_rejects_bad_states() {
+        let mut chip = running_chip();
+        let before = chip.save_state();
+
+        assert_eq!(chip.load_state(b"nope, not a state"), Err(StateError::BadMagic));
+        assert_eq!(chip.load_state(b"CH"), Err(StateError::Truncated));
+
+        let mut state = before.clone();
+        state[4] = 0xFF;
+        assert_eq!(chip.load_state(&state), Err(StateError::UnsupportedVersion(0x00FF)));
+
+        let state = &before[..before.len() - 3];
+        assert_eq!(chip.load_state(state), Err(StateError::Truncated));
+
+        // header and the variant section only
+        let state = &before[..6 + 9];
+        assert_eq!(
+            chip.load_state(state),
+            Err(StateError::MissingSection(String::from("CPU")))
+        );
+
+        let mut state = before.clone();
+        let quirks = state.windows(4).position(|tag| tag == b"QURK").unwrap() + 8;
+        state[quirks + 1] = 3;
+        assert_eq!(
+            chip.load_state(&state),
+            Err(StateError::BadSection(String::from("QURK")))
+        );
+
+        // every section is required, the generator one too
+        let mut state = before.clone();
+        let random = state.windows(4).position(|tag| tag == b"RAND").unwrap();
+        state.drain(random..random + 8 + 4);
+        assert_eq!(
+            chip.load_state(&state),
+            Err(StateError::MissingSection(String::from("RAND")))
+        );
+
+        // a failed load leaves the machine alone
+        assert_eq!(chip.save_state(), before);
+    }
+
+    #[test]
+    fn test_unknown_sections_are_skipped() {
+        let chip = running_chip();
+        let mut state = chip.save_state();
+        state.extend_from_slice(b"NEW!");
+        state.extend_from_slice(&3u32.to_le_bytes());
+        state.extend_from_slice(&[1, 2, 3]);
+
+        let mut restored = Chip8::new();
+        restored.load_state(&state).unwrap();
+
+        assert_same_machine(&chip, &restored);
+    }
+
+    #[test]
+    fn test_json() {
+        let mut chip = running_chip();
+        chip.OP_00FF().unwrap();
+        let json = chip.save_state_json();
+
+        assert!(json.starts_with("{\n"));
+        assert!(json.ends_with('}'));
+        assert!(json.contains("\"variant\": \"chip8\""));
+        assert!(json.contains("\"pc\": 522"));
+        assert!(json.contains("\"jump_uses_vx\": true"));
+        assert!(json.contains("\"load_store_index\": \"by_x\""));
+        assert!(json.contains("\"audio_pattern\": null"));
+        assert!(json.contains(&format!("\"{}\"", "0".repeat(128))));
+    }
+}
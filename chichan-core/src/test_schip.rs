@@ -0,0 +1,160 @@
+// Tests
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test_schip {
+    use super::super::chip8::{Chip8, Chip8Error, StepEvent, Variant};
+
+    const START_ADDRESS: usize = 0x200;
+
+    fn lit(chip: &Chip8) -> Vec<(usize, usize)> {
+        let mut pixels = Vec::new();
+        for x in 0..chip.width() {
+            for y in 0..chip.height() {
+                if chip.color_index(x, y) != 0 {
+                    pixels.push((x, y));
+                }
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn test_hires_switch() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 10, 0, true);
+
+        chip.OP_00FF().unwrap();
+        assert!(chip.video.hires());
+        assert_eq!((chip.width(), chip.height()), (128, 64));
+        assert_eq!(chip.video.rows().count(), 64);
+        assert_eq!(chip.video.row(0).len(), 128);
+        assert!(lit(&chip).is_empty());
+
+        chip.video.set(0, 10, 0, true);
+        chip.OP_00FE().unwrap();
+        assert!(!chip.video.hires());
+        assert_eq!((chip.width(), chip.height()), (64, 32));
+        assert!(lit(&chip).is_empty());
+    }
+
+    #[test]
+    fn test_OP_00CN() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 5, 1, true);
+        chip.opcode = 0x00C3;
+
+        chip.OP_00CN().unwrap();
+
+        assert_eq!(lit(&chip), vec![(5, 4)]);
+    }
+
+    #[test]
+    fn test_OP_00FB_00FC() {
+        let mut chip = Chip8::new();
+        chip.OP_00FF().unwrap();
+        chip.video.set(0, 10, 2, true);
+        chip.video.set(0, 126, 3, true);
+
+        chip.OP_00FB().unwrap();
+        assert_eq!(lit(&chip), vec![(14, 2)]);
+
+        chip.OP_00FC().unwrap();
+        chip.OP_00FC().unwrap();
+        assert_eq!(lit(&chip), vec![(6, 2)]);
+    }
+
+    #[test]
+    fn test_OP_00FD() {
+        let mut chip = Chip8::new();
+        chip.variant = Variant::SuperChip;
+        chip.load_rom_bytes(&[0x00, 0xFD]).unwrap();
+
+        assert_eq!(chip.cycle(), Ok(StepEvent::Exited));
+        assert_eq!(chip.pc, START_ADDRESS as u16);
+        assert_eq!(chip.run_frame(), Ok(StepEvent::Exited));
+    }
+
+    #[test]
+    fn test_schip_opcodes_need_schip() {
+        for opcode in [0x00C4, 0x00FB, 0x00FC, 0x00FD, 0x00FE, 0x00FF, 0xF130, 0xF275, 0xF385] {
+            assert!(!Variant::Chip8.supports(opcode), "{:04X}", opcode);
+            assert!(Variant::SuperChip.supports(opcode), "{:04X}", opcode);
+            assert!(Variant::XoChip.supports(opcode), "{:04X}", opcode);
+        }
+        assert!(Variant::Chip8.supports(0x00E0));
+        assert!(Variant::Chip8.supports(0xF165));
+
+        // HIGH in a plain CHIP-8 rom is an extension it doesn't have
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x00, 0xFF]).unwrap();
+        assert_eq!(chip.cycle(), Err(Chip8Error::InvalidOpcode { opcode: 0x00FF, pc: 0x200 }));
+        assert!(!chip.video.hires());
+
+        let mut chip = Chip8::new();
+        chip.variant = Variant::SuperChip;
+        chip.load_rom_bytes(&[0x00, 0xFF]).unwrap();
+        assert_eq!(chip.cycle(), Ok(StepEvent::Executed));
+        assert!(chip.video.hires());
+    }
+
+    #[test]
+    fn test_OP_Dxy0() {
+        let mut chip = Chip8::new();
+        chip.OP_00FF().unwrap();
+        for row in 0..16 {
+            chip.memory[0x300 + row * 2] = 0x80;
+            chip.memory[0x300 + row * 2 + 1] = 0x01;
+        }
+        chip.index = 0x300;
+        chip.registers[0x0] = 20;
+        chip.registers[0x1] = 30;
+        chip.opcode = 0xD010;
+
+        assert_eq!(chip.OP_Dxyn(), Ok(StepEvent::Drew));
+
+        let pixels = lit(&chip);
+        assert_eq!(pixels.len(), 32);
+        assert_eq!(chip.registers[0xF], 0);
+
+        chip.OP_Dxyn().unwrap();
+        assert!(lit(&chip).is_empty());
+        assert_eq!(chip.registers[0xF], 1);
+    }
+
+    #[test]
+    fn test_OP_Fx30() {
+        let mut chip = Chip8::new();
+        chip.registers[0x2] = 0x7;
+        chip.opcode = 0xF230;
+
+        chip.OP_Fx30().unwrap();
+
+        assert_eq!(chip.index, 0xA0 + 70);
+        assert_eq!(&chip.memory[chip.index as usize..chip.index as usize + 2], &[0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_OP_Fx75_Fx85() {
+        let mut chip = Chip8::new();
+        for x in 0..8 {
+            chip.registers[x] = x as u8 + 1;
+        }
+        chip.opcode = 0xF775;
+        chip.OP_Fx75().unwrap();
+
+        chip.registers = [0; 16];
+        chip.opcode = 0xF385;
+        chip.OP_Fx85().unwrap();
+
+        assert_eq!(&chip.registers[..5], &[1, 2, 3, 4, 0]);
+        assert_eq!(&chip.rpl_flags[..8], &[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_sys_call_is_unknown() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0x01, 0xE0]).unwrap();
+
+        assert!(chip.cycle().is_err());
+    }
+}
@@ -0,0 +1,294 @@
+// Tests
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test_xochip {
+    use super::super::audio::{self, Buzzer};
+    use super::super::chip8::{Chip8, Chip8Error, RomError, StepEvent, Variant};
+    use super::super::quirks::Quirks;
+
+    const START_ADDRESS: usize = 0x200;
+
+    fn xochip() -> Chip8 {
+        let mut chip = Chip8::new();
+        chip.variant = Variant::XoChip;
+        chip
+    }
+
+    fn lit(chip: &Chip8, plane: usize) -> usize {
+        (0..chip.height())
+            .map(|y| chip.video.row_bits(plane, y).count_ones() as usize)
+            .sum()
+    }
+
+    #[test]
+    fn test_variant_from_str() {
+        assert_eq!("chip8".parse(), Ok(Variant::Chip8));
+        assert_eq!("schip".parse(), Ok(Variant::SuperChip));
+        assert_eq!("xochip".parse(), Ok(Variant::XoChip));
+        assert!("megachip".parse::<Variant>().is_err());
+
+        assert_eq!(Variant::Chip8.default_quirks(), Quirks::default());
+        assert_eq!(Variant::XoChip.default_quirks(), Quirks::XO_CHIP);
+    }
+
+    #[test]
+    fn test_memory_size() {
+        let mut chip = Chip8::new();
+        assert_eq!(chip.mem_size(), 4096);
+
+        chip.variant = Variant::XoChip;
+        assert_eq!(chip.mem_size(), 0x10000);
+    }
+
+    #[test]
+    fn test_load_large_rom() {
+        let rom = vec![0xAB; 0x10000 - START_ADDRESS];
+
+        // too big for the classic 4 KiB
+        match Chip8::new().load_rom_bytes(&rom) {
+            Err(RomError::TooLarge { available, .. }) => assert_eq!(available, 4096 - START_ADDRESS),
+            other => panic!("expected TooLarge, got {:?}", other),
+        }
+
+        let mut chip = xochip();
+        assert_eq!(chip.load_rom_bytes(&rom).unwrap(), rom.len());
+        assert_eq!(chip.memory[0xFFFF], 0xAB);
+    }
+
+    #[test]
+    fn test_high_memory_access() {
+        let mut chip = xochip();
+        chip.registers[0x0] = 123;
+        chip.index = 0xF000;
+        chip.opcode = 0xF033;
+
+        chip.OP_Fx33().unwrap();
+        assert_eq!(&chip.memory[0xF000..0xF003], &[1, 2, 3]);
+
+        chip.index = 0xFFFE;
+        assert_eq!(chip.OP_Fx33(), Err(Chip8Error::MemoryOutOfBounds { addr: 0x10000 }));
+    }
+
+    #[test]
+    fn test_OP_F000() {
+        let mut chip = xochip();
+        chip.load_rom_bytes(&[0xF0, 0x00, 0xE1, 0x23, 0x60, 0x01]).unwrap();
+
+        assert_eq!(chip.cycle(), Ok(StepEvent::Executed));
+        assert_eq!(chip.index, 0xE123);
+        assert_eq!(chip.pc, START_ADDRESS as u16 + 4);
+
+        chip.cycle().unwrap();
+        assert_eq!(chip.registers[0x0], 1);
+    }
+
+    #[test]
+    fn test_skip_over_long_load() {
+        // SE V0, 0 then F000 1234: only XO-CHIP has the 4-byte long load to skip,
+        // elsewhere F000 is a 2-byte unknown opcode and 1234 the instruction after it
+        for (variant, pc) in [(Variant::Chip8, 0x204), (Variant::SuperChip, 0x204), (Variant::XoChip, 0x206)] {
+            let mut chip = Chip8::new();
+            chip.variant = variant;
+            chip.load_rom_bytes(&[0x30, 0x00, 0xF0, 0x00, 0x12, 0x34]).unwrap();
+
+            chip.cycle().unwrap();
+            assert_eq!(chip.pc, pc, "{:?}", variant);
+        }
+    }
+
+    #[test]
+    fn test_long_load_needs_xochip() {
+        // SE V0, 1 doesn't skip, F000 1234 runs
+        let rom = [0x30, 0x01, 0xF0, 0x00, 0x12, 0x34];
+
+        for variant in [Variant::Chip8, Variant::SuperChip] {
+            let mut chip = Chip8::new();
+            chip.variant = variant;
+            chip.load_rom_bytes(&rom).unwrap();
+
+            chip.cycle().unwrap();
+            assert_eq!(chip.cycle(), Err(Chip8Error::InvalidOpcode { opcode: 0xF000, pc: 0x202 }));
+        }
+
+        let mut chip = xochip();
+        chip.load_rom_bytes(&rom).unwrap();
+        chip.cycle().unwrap();
+        chip.cycle().unwrap();
+        assert_eq!(chip.index, 0x1234);
+        assert_eq!(chip.pc, START_ADDRESS as u16 + 6);
+    }
+
+    #[test]
+    fn test_xochip_opcodes_need_xochip() {
+        for opcode in [0x5122, 0x5123, 0xF000, 0xF201, 0xF002, 0xF33A] {
+            assert!(!Variant::Chip8.supports(opcode), "{:04X}", opcode);
+            assert!(!Variant::SuperChip.supports(opcode), "{:04X}", opcode);
+            assert!(Variant::XoChip.supports(opcode), "{:04X}", opcode);
+        }
+
+        let mut chip = Chip8::new();
+        chip.variant = Variant::SuperChip;
+        chip.load_rom_bytes(&[0x51, 0x22]).unwrap();
+        assert_eq!(chip.cycle(), Err(Chip8Error::InvalidOpcode { opcode: 0x5122, pc: 0x200 }));
+    }
+
+    #[test]
+    fn test_OP_5xy2_5xy3() {
+        let mut chip = xochip();
+        for x in 0..16 {
+            chip.registers[x] = x as u8 * 10;
+        }
+        chip.index = 0x400;
+
+        chip.opcode = 0x5252;
+        chip.OP_5xy2().unwrap();
+        assert_eq!(&chip.memory[0x400..0x405], &[20, 30, 40, 50, 0]);
+        assert_eq!(chip.index, 0x400);
+
+        // reversed range
+        chip.opcode = 0x5312;
+        chip.OP_5xy2().unwrap();
+        assert_eq!(&chip.memory[0x400..0x403], &[30, 20, 10]);
+
+        chip.registers = [0; 16];
+        chip.opcode = 0x5A83;
+        chip.OP_5xy3().unwrap();
+        assert_eq!(&chip.registers[0x8..0xB], &[10, 20, 30]);
+        assert_eq!(chip.index, 0x400);
+    }
+
+    #[test]
+    fn test_OP_5xy1_is_unknown() {
+        let mut chip = xochip();
+        chip.load_rom_bytes(&[0x51, 0x21]).unwrap();
+
+        assert!(chip.cycle().is_err());
+    }
+
+    #[test]
+    fn test_two_plane_sprite() {
+        let mut chip = xochip();
+        chip.memory[0x300] = 0xFF;
+        chip.memory[0x301] = 0x0F;
+        chip.index = 0x300;
+        chip.registers[0x0] = 8;
+        chip.opcode = 0xF301;
+        chip.OP_Fn01().unwrap();
+        assert_eq!(chip.planes, 0b11);
+
+        chip.opcode = 0xD011;
+        assert_eq!(chip.OP_Dxyn(), Ok(StepEvent::Drew));
+
+        // plane 2 reads its row right after plane 1's
+        assert_eq!(lit(&chip, 0), 8);
+        assert_eq!(lit(&chip, 1), 4);
+        assert_eq!(chip.registers[0xF], 0);
+
+        let mut colors = [0; 4];
+        for x in 0..chip.width() {
+            colors[chip.color_index(x, 0)] += 1;
+        }
+        assert_eq!(colors, [56, 4, 0, 4]);
+
+        // a collision on either plane sets VF
+        chip.opcode = 0xF201;
+        chip.OP_Fn01().unwrap();
+        chip.opcode = 0xD011;
+        chip.OP_Dxyn().unwrap();
+        assert_eq!(chip.registers[0xF], 1);
+        assert_eq!(lit(&chip, 0), 8);
+        assert_eq!(lit(&chip, 1), 4);
+    }
+
+    #[test]
+    fn test_clear_selected_planes() {
+        let mut chip = xochip();
+        chip.video.set(0, 0, 0, true);
+        chip.video.set(1, 0, 0, true);
+
+        chip.opcode = 0xF201;
+        chip.OP_Fn01().unwrap();
+        chip.OP_00E0().unwrap();
+        assert_eq!(lit(&chip, 0), 1);
+        assert_eq!(lit(&chip, 1), 0);
+
+        chip.opcode = 0xF001;
+        chip.OP_Fn01().unwrap();
+        chip.OP_00E0().unwrap();
+        assert_eq!(lit(&chip, 0), 1);
+    }
+
+    #[test]
+    fn test_scroll_selected_planes() {
+        let mut chip = xochip();
+        chip.video.set(0, 0, 0, true);
+        chip.video.set(1, 0, 0, true);
+
+        chip.opcode = 0xF201;
+        chip.OP_Fn01().unwrap();
+        chip.opcode = 0x00C1;
+        chip.OP_00CN().unwrap();
+
+        assert!(chip.video.is_lit(0, 0, 0));
+        assert!(!chip.video.is_lit(1, 0, 0));
+        assert!(chip.video.is_lit(1, 0, 1));
+    }
+
+    #[test]
+    fn test_OP_F002_Fx3A() {
+        let mut chip = xochip();
+        assert_eq!(chip.audio_pattern, None);
+        assert_eq!(chip.pitch, audio::DEFAULT_PITCH);
+
+        for x in 0..16 {
+            chip.memory[0x300 + x] = x as u8;
+        }
+        chip.index = 0x300;
+        chip.opcode = 0xF002;
+        chip.OP_F002().unwrap();
+
+        let expected: Vec<u8> = (0..16).collect();
+        assert_eq!(&chip.audio_pattern.unwrap()[..], &expected[..]);
+
+        chip.registers[0x4] = 112;
+        chip.opcode = 0xF43A;
+        chip.OP_Fx3A().unwrap();
+        assert_eq!(chip.pitch, 112);
+    }
+
+    #[test]
+    fn test_pattern_rate() {
+        assert_eq!(audio::pattern_rate(64), 4000.0);
+        assert!((audio::pattern_rate(112) - 8000.0).abs() < 0.01);
+        assert!((audio::pattern_rate(16) - 2000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_pattern_playback() {
+        // 4000 samples per second at pitch 64 is one pattern bit per sample
+        let mut buzzer = Buzzer::new(4000);
+        buzzer.volume = 0.5;
+        buzzer.pattern = Some([0xA0; 16]);
+
+        let samples: Vec<f32> = (0..8).map(|_| buzzer.next_sample(true)).collect();
+
+        assert_eq!(samples, vec![0.5, -0.5, 0.5, -0.5, -0.5, -0.5, -0.5, -0.5]);
+        assert_eq!(buzzer.next_sample(false), 0.0);
+    }
+
+    #[test]
+    fn test_stay_on_the_last_instruction() {
+        // pc wraps to 0 after fetching from 0xFFFE, stepping back must wrap too
+        let mut chip = xochip();
+        chip.memory[0xFFFE] = 0xF3;
+        chip.memory[0xFFFF] = 0x0A;
+        chip.pc = 0xFFFE;
+        assert_eq!(chip.cycle(), Ok(StepEvent::WaitingForKey));
+        assert_eq!(chip.pc, 0xFFFE);
+
+        chip.memory[0xFFFE] = 0x00;
+        chip.memory[0xFFFF] = 0xFD;
+        assert_eq!(chip.cycle(), Ok(StepEvent::Exited));
+        assert_eq!(chip.pc, 0xFFFE);
+    }
+}
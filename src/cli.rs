@@ -0,0 +1,278 @@
+// Command line parsing for the chichan binary
+use chichan_core::audio;
+use chichan_core::chip8::{self, UnknownOpcodePolicy, Variant};
+use chichan_core::filter::Filter;
+use chichan_core::palette::Palette;
+use chichan_core::quirks::Quirks;
+use chichan_core::rewind;
+
+pub const DEFAULT_SPEED: u32 = chip8::DEFAULT_SPEED;
+pub const DEFAULT_SCALE: usize = 8;
+/// Largest `--scale`, a 2048x1024 window
+pub const MAX_SCALE: usize = 32;
+
+pub const USAGE: &str = "\
+Usage: chichan [run] [OPTIONS] <ROM>
+       chichan disasm [--variant <VARIANT>] <ROM>
+       chichan asm <SOURCE> [-o <ROM>]
+
+Arguments:
+  <ROM>                 Path to the CHIP-8 rom to run
+
+Options:
+  --speed <IPS>         Instructions executed per second [default: 700]
+  --scale <N>           Size of one CHIP-8 pixel on screen, 1 to 32 [default: 8]
+  --variant <VARIANT>   Instruction set and memory size (chip8, schip, xochip) [default: chip8]
+  --quirks <PROFILE>    Quirks profile (vip, chip48, schip, xochip) [default: the variant's]
+  --seed <N>            Seed for the random numbers of Cxkk, 1 or more, the same seed replays the same game [default: random]
+  --unknown-opcodes <POLICY>
+                        What to do on an unknown opcode (ignore, log, halt) [default: halt]
+  --tone <HZ>           Buzzer frequency [default: 440]
+  --volume <0-1>        Buzzer volume, 0 mutes it [default: 0.25]
+  --keymap <FILE>       Key map file rebinding the keypad
+  --palette <NAME>      Display colors (classic, amber, green, lcd, contrast, colorblind) [default: classic]
+  --filter <FILTER>     Display filter hiding sprite flicker
+                        (off, merge, phosphor, phosphor:<decay>, phosphor:<decay>:<frames>) [default: off]
+  --theme <FILE>        Theme file for the colors and the filter, --palette and --filter win over it
+                        [default: <ROM>.theme if it exists]
+  --state <FILE>        Save state to resume from after loading the rom
+  --rewind <SECONDS>    Seconds of history kept for rewinding, 0 disables it [default: 10]
+  --paused              Start with the emulator paused, P resumes it (not with --headless)
+  --headless            Run without opening a window
+  --frames <N>          With --headless, run N frames as fast as possible then print the screen,
+                        registers and status; exits with 1 if the rom faulted
+  --dump <FILE>         With --frames, also write the screen to FILE, a PNG if it ends in .png
+  --debug               Run under the debugger, driven from the terminal
+  -h, --help            Print this help";
+
+/// Launch options collected from argv
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub rom_path: String,
+    pub speed: u32,
+    pub scale: usize,
+    pub variant: Variant,
+    pub quirks: Quirks,
+    pub seed: Option<u32>,
+    pub unknown_opcodes: UnknownOpcodePolicy,
+    pub keymap: Option<String>,
+    /// `None` unless `--palette` is given, the theme file or the default fills it in
+    pub palette: Option<Palette>,
+    /// `None` unless `--filter` is given, the theme file or the default fills it in
+    pub filter: Option<Filter>,
+    /// Theme file for whichever of `palette` and `filter` the command line leaves out
+    pub theme: Option<String>,
+    pub state: Option<String>,
+    pub rewind: u32,
+    pub tone: f32,
+    pub volume: f32,
+    pub paused: bool,
+    pub headless: bool,
+    /// Frames a headless run stops after, it runs until the rom exits otherwise
+    pub frames: Option<u32>,
+    pub dump: Option<String>,
+    pub debug: bool,
+}
+
+/// What the binary should do after parsing argv
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Options),
+    /// Print the rom as assembly
+    Disasm { rom_path: String, variant: Variant },
+    /// Assemble a source file into a rom
+    Asm { source_path: String, output_path: String },
+    Help,
+}
+
+/// Parse the arguments following the program name
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    if args.peek().map(String::as_str) == Some("disasm") {
+        args.next();
+        return parse_disasm(args);
+    }
+    if args.peek().map(String::as_str) == Some("asm") {
+        args.next();
+        return parse_asm(args);
+    }
+
+    // `chichan run rom.ch8` is the same as `chichan rom.ch8`
+    if args.peek().map(String::as_str) == Some("run") {
+        args.next();
+    }
+
+    parse_run(args)
+}
+
+fn parse_disasm<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut rom_path: Option<String> = None;
+    let mut variant = Variant::Chip8;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--variant" => variant = value_of(&arg, args.next())?.parse()?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if rom_path.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                rom_path = Some(arg);
+            }
+        }
+    }
+
+    let rom_path = rom_path.ok_or_else(|| String::from("missing <ROM> argument"))?;
+
+    Ok(Command::Disasm { rom_path, variant })
+}
+
+fn parse_asm<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut source_path: Option<String> = None;
+    let mut output_path = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-o" | "--output" => output_path = Some(value_of(&arg, args.next())?),
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if source_path.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                source_path = Some(arg);
+            }
+        }
+    }
+
+    let source_path = source_path.ok_or_else(|| String::from("missing <SOURCE> argument"))?;
+    // out.asm -> out.ch8 when no output is given
+    let output_path = output_path.unwrap_or_else(|| {
+        let stem = source_path.strip_suffix(".asm").unwrap_or(&source_path);
+        format!("{}.ch8", stem)
+    });
+
+    Ok(Command::Asm {
+        source_path,
+        output_path,
+    })
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut rom_path: Option<String> = None;
+    let mut speed = DEFAULT_SPEED;
+    let mut scale = DEFAULT_SCALE;
+    let mut variant = Variant::Chip8;
+    let mut quirks: Option<Quirks> = None;
+    let mut seed = None;
+    let mut unknown_opcodes = UnknownOpcodePolicy::Halt;
+    let mut keymap = None;
+    let mut palette = None;
+    let mut filter = None;
+    let mut theme = None;
+    let mut state = None;
+    let mut rewind = rewind::DEFAULT_SECONDS;
+    let mut tone = audio::DEFAULT_FREQUENCY;
+    let mut volume = audio::DEFAULT_VOLUME;
+    let mut paused = false;
+    let mut headless = false;
+    let mut frames = None;
+    let mut dump = None;
+    let mut debug = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--speed" => speed = parse_number(&arg, args.next())?,
+            "--scale" => scale = parse_number(&arg, args.next())?,
+            "--variant" => variant = value_of(&arg, args.next())?.parse()?,
+            "--quirks" => quirks = Some(value_of(&arg, args.next())?.parse()?),
+            "--seed" => seed = Some(parse_number(&arg, args.next())?),
+            "--unknown-opcodes" => unknown_opcodes = value_of(&arg, args.next())?.parse()?,
+            "--keymap" => keymap = Some(value_of(&arg, args.next())?),
+            "--palette" => palette = Some(value_of(&arg, args.next())?.parse()?),
+            "--filter" => filter = Some(value_of(&arg, args.next())?.parse()?),
+            "--theme" => theme = Some(value_of(&arg, args.next())?),
+            "--state" => state = Some(value_of(&arg, args.next())?),
+            "--rewind" => rewind = parse_number(&arg, args.next())?,
+            "--tone" => tone = parse_number(&arg, args.next())?,
+            "--volume" => volume = parse_number(&arg, args.next())?,
+            "--paused" => paused = true,
+            "--headless" => headless = true,
+            "--frames" => frames = Some(parse_number(&arg, args.next())?),
+            "--dump" => dump = Some(value_of(&arg, args.next())?),
+            "--debug" => debug = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if rom_path.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                rom_path = Some(arg);
+            }
+        }
+    }
+
+    if speed == 0 {
+        return Err(String::from("--speed must be greater than 0"));
+    }
+    if !(1..=MAX_SCALE).contains(&scale) {
+        return Err(format!("--scale must be between 1 and {}", MAX_SCALE));
+    }
+    if seed == Some(0) {
+        return Err(String::from("--seed must be greater than 0"));
+    }
+    if tone.is_nan() || tone <= 0.0 {
+        return Err(String::from("--tone must be greater than 0"));
+    }
+    if !(0.0..=1.0).contains(&volume) {
+        return Err(String::from("--volume must be between 0 and 1"));
+    }
+    // nothing could resume a machine without a window
+    if paused && headless {
+        return Err(String::from("--paused needs a window, it can't be used with --headless"));
+    }
+    if frames.is_some() && !headless {
+        return Err(String::from("--frames needs --headless"));
+    }
+    if dump.is_some() && frames.is_none() {
+        return Err(String::from("--dump needs --frames"));
+    }
+
+    let rom_path = rom_path.ok_or_else(|| String::from("missing <ROM> argument"))?;
+    let quirks = quirks.unwrap_or_else(|| variant.default_quirks());
+
+    Ok(Command::Run(Options {
+        rom_path,
+        speed,
+        scale,
+        variant,
+        quirks,
+        seed,
+        unknown_opcodes,
+        keymap,
+        palette,
+        filter,
+        theme,
+        state,
+        rewind,
+        tone,
+        volume,
+        paused,
+        headless,
+        frames,
+        dump,
+        debug,
+    }))
+}
+
+fn value_of(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value_of(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, flag))
+}
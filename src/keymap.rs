@@ -0,0 +1,176 @@
+// Keyboard to CHIP-8 keypad mapping
+use coffee::input::keyboard::KeyCode;
+use std::fs;
+
+/// Which keyboard key drives each of the 16 keypad keys, indexed by keypad key
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMap {
+    pub keys: [KeyCode; 16],
+}
+
+impl Default for KeyMap {
+    /// The usual layout, the left side of a QWERTY keyboard:
+    ///
+    /// | 1 | 2 | 3 | C |      | 1 | 2 | 3 | 4 |
+    /// | 4 | 5 | 6 | D |      | Q | W | E | R |
+    /// | 7 | 8 | 9 | E |  ->  | A | S | D | F |
+    /// | A | 0 | B | F |      | Z | X | C | V |
+    fn default() -> Self {
+        KeyMap {
+            keys: [
+                KeyCode::X,    // 0
+                KeyCode::Key1, // 1
+                KeyCode::Key2, // 2
+                KeyCode::Key3, // 3
+                KeyCode::Q,    // 4
+                KeyCode::W,    // 5
+                KeyCode::E,    // 6
+                KeyCode::A,    // 7
+                KeyCode::S,    // 8
+                KeyCode::D,    // 9
+                KeyCode::Z,    // A
+                KeyCode::C,    // B
+                KeyCode::Key4, // C
+                KeyCode::R,    // D
+                KeyCode::F,    // E
+                KeyCode::V,    // F
+            ],
+        }
+    }
+}
+
+impl KeyMap {
+    /// Read a key map file, see `parse` for the format
+    pub fn load(path: &str) -> Result<KeyMap, String> {
+        let source = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        Self::parse(&source).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    /// Parse a key map from text. One binding per line, `<keypad key> = <keyboard key>`:
+    ///
+    /// ```text
+    /// # AZERTY
+    /// 4 = A
+    /// 5 = Z
+    /// A = W
+    /// ```
+    ///
+    /// Keypad keys are a single hex digit, keyboard keys are named after coffee's `KeyCode`
+    /// (`A`, `1`, `Numpad5`, `Up`, `Space`...). Keys that aren't listed keep the default binding
+    pub fn parse(source: &str) -> Result<KeyMap, String> {
+        let mut map = KeyMap::default();
+
+        for (number, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+
+            let mut parts = line.splitn(2, '=');
+            let chip_key = parts.next().unwrap_or("").trim();
+            let key_name = parts
+                .next()
+                .ok_or_else(|| error(String::from("expected '<keypad key> = <keyboard key>'")))?
+                .trim();
+
+            let chip_key = u8::from_str_radix(chip_key, 16)
+                .ok()
+                .filter(|key| *key < 16)
+                .ok_or_else(|| error(format!("'{}' is not a keypad key (0-F)", chip_key)))?;
+            let key_code = key_code(key_name)
+                .ok_or_else(|| error(format!("unknown keyboard key '{}'", key_name)))?;
+
+            map.keys[chip_key as usize] = key_code;
+        }
+
+        Ok(map)
+    }
+}
+
+/// Look up a keyboard key by name
+fn key_code(name: &str) -> Option<KeyCode> {
+    let key = match name {
+        "0" | "Key0" => KeyCode::Key0,
+        "1" | "Key1" => KeyCode::Key1,
+        "2" | "Key2" => KeyCode::Key2,
+        "3" | "Key3" => KeyCode::Key3,
+        "4" | "Key4" => KeyCode::Key4,
+        "5" | "Key5" => KeyCode::Key5,
+        "6" | "Key6" => KeyCode::Key6,
+        "7" | "Key7" => KeyCode::Key7,
+        "8" | "Key8" => KeyCode::Key8,
+        "9" | "Key9" => KeyCode::Key9,
+        "A" => KeyCode::A,
+        "B" => KeyCode::B,
+        "C" => KeyCode::C,
+        "D" => KeyCode::D,
+        "E" => KeyCode::E,
+        "F" => KeyCode::F,
+        "G" => KeyCode::G,
+        "H" => KeyCode::H,
+        "I" => KeyCode::I,
+        "J" => KeyCode::J,
+        "K" => KeyCode::K,
+        "L" => KeyCode::L,
+        "M" => KeyCode::M,
+        "N" => KeyCode::N,
+        "O" => KeyCode::O,
+        "P" => KeyCode::P,
+        "Q" => KeyCode::Q,
+        "R" => KeyCode::R,
+        "S" => KeyCode::S,
+        "T" => KeyCode::T,
+        "U" => KeyCode::U,
+        "V" => KeyCode::V,
+        "W" => KeyCode::W,
+        "X" => KeyCode::X,
+        "Y" => KeyCode::Y,
+        "Z" => KeyCode::Z,
+        "Numpad0" => KeyCode::Numpad0,
+        "Numpad1" => KeyCode::Numpad1,
+        "Numpad2" => KeyCode::Numpad2,
+        "Numpad3" => KeyCode::Numpad3,
+        "Numpad4" => KeyCode::Numpad4,
+        "Numpad5" => KeyCode::Numpad5,
+        "Numpad6" => KeyCode::Numpad6,
+        "Numpad7" => KeyCode::Numpad7,
+        "Numpad8" => KeyCode::Numpad8,
+        "Numpad9" => KeyCode::Numpad9,
+        "Add" => KeyCode::Add,
+        "Subtract" => KeyCode::Subtract,
+        "Multiply" => KeyCode::Multiply,
+        "Divide" => KeyCode::Divide,
+        "Decimal" => KeyCode::Decimal,
+        "NumpadEnter" => KeyCode::NumpadEnter,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Space" => KeyCode::Space,
+        "Return" | "Enter" => KeyCode::Return,
+        "Tab" => KeyCode::Tab,
+        "Comma" => KeyCode::Comma,
+        "Period" => KeyCode::Period,
+        "Semicolon" => KeyCode::Semicolon,
+        "Colon" => KeyCode::Colon,
+        "Apostrophe" => KeyCode::Apostrophe,
+        "Slash" => KeyCode::Slash,
+        "Backslash" => KeyCode::Backslash,
+        "Minus" => KeyCode::Minus,
+        "Equals" => KeyCode::Equals,
+        "LBracket" => KeyCode::LBracket,
+        "RBracket" => KeyCode::RBracket,
+        "Grave" => KeyCode::Grave,
+        "LShift" => KeyCode::LShift,
+        "RShift" => KeyCode::RShift,
+        "LControl" => KeyCode::LControl,
+        "RControl" => KeyCode::RControl,
+        "LAlt" => KeyCode::LAlt,
+        "RAlt" => KeyCode::RAlt,
+        _ => return None,
+    };
+
+    Some(key)
+}
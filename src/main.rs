@@ -1,33 +1,156 @@
-use coffee::graphics::{Color, Frame, Window, WindowSettings, Rectangle, Shape, Mesh, Transformation, Vector};
+use coffee::graphics::{Color, Frame, Gpu, Image, Point, Quad, Window, WindowSettings};
+use coffee::input::keyboard::KeyCode;
+use coffee::input::KeyboardAndMouse;
 use coffee::load::Task;
 use coffee::{Game, Result, Timer};
+use std::fs;
+use std::process;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
 
-mod chip8;
+use chichan_core::filter::Renderer;
+use chichan_core::frontend::{self, Frontend};
+use chichan_core::{asm, chip8, debugger, disasm, headless, rewind, Chip8, Palette};
 
-mod test_chip8;
+mod cli;
+mod keymap;
+mod repl;
+mod screenshot;
+mod sound;
+mod theme;
 
-type Chip8 = chip8::Chip8;
+#[allow(clippy::module_inception)]
+mod test_cli;
+#[allow(clippy::module_inception)]
+mod test_keymap;
+#[allow(clippy::module_inception)]
+mod test_repl;
+#[allow(clippy::module_inception)]
+mod test_screenshot;
+#[allow(clippy::module_inception)]
+mod test_theme;
+#[allow(clippy::module_inception)]
+mod test_window;
 
 //Graphic setup
-const PIXEL_SIZE: usize = 8;
 const VIDEO_WIDTH: usize = 64;
 const VIDEO_HEIGHT: usize = 32;
+const MARGIN: usize = 10;
+
+// held to play the machine backwards
+const REWIND_KEY: KeyCode = KeyCode::Back;
+
+// pauses and resumes the machine
+const PAUSE_KEY: KeyCode = KeyCode::P;
+
+// switches to the next palette preset
+const PALETTE_KEY: KeyCode = KeyCode::Tab;
+
+// F1-F10 pick the save slot, with shift held the slot is saved instead of loaded
+const SLOT_KEYS: [KeyCode; 10] = [
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+];
+
+// coffee builds the game through `Game::load`, which takes no arguments,
+// so the parsed options, the loaded chip and the display's look are parked here for it to pick up
+static LAUNCH: Mutex<Option<(cli::Options, Chip8, keymap::KeyMap, theme::Theme)>> = Mutex::new(None);
 
 fn main() -> Result<()>{
-    // let mut chip: Chip8 = Chip8::new();
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli::Command::Run(options)) => options,
+        Ok(cli::Command::Disasm { rom_path, variant }) => {
+            match fs::read(&rom_path) {
+                Ok(rom) => print!("{}", disasm::disassemble(&rom, variant)),
+                Err(err) => {
+                    eprintln!("error: {}: {}", rom_path, err);
+                    process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Ok(cli::Command::Asm { source_path, output_path }) => {
+            let source = match fs::read_to_string(&source_path) {
+                Ok(source) => source,
+                Err(err) => {
+                    eprintln!("error: {}: {}", source_path, err);
+                    process::exit(1);
+                }
+            };
+            let rom = match asm::assemble(&source) {
+                Ok(rom) => rom,
+                Err(err) => {
+                    eprintln!("error: {}: {}", source_path, err);
+                    process::exit(1);
+                }
+            };
+            if let Err(err) = fs::write(&output_path, &rom) {
+                eprintln!("error: {}: {}", output_path, err);
+                process::exit(1);
+            }
+            return Ok(());
+        }
+        Ok(cli::Command::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            process::exit(2);
+        }
+    };
 
-    // load rom
-    // let bc_test = String::from("/home/riyan/code/chichan/src/BC_test.ch8");
-    // let op_test = String::from("/home/riyan/code/chichan/src/test_opcode.ch8");
-    // let tetris = String::from("/home/riyan/code/chichan/src/tetris.ch8");
-    // let rom_path = op_test;
-    // chip.load_rom(&rom_path);
+    let chip = load_chip(&options);
 
-    // let mut buffer: Vec<u32> = vec![0; WIN_WIDTH * WIN_HEIGHT];
+    let keymap = match &options.keymap {
+        Some(path) => keymap::KeyMap::load(path).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }),
+        None => keymap::KeyMap::default(),
+    };
+
+    // a rom's own theme applies unless one is given
+    let theme = options.theme.clone().or_else(|| theme::rom_theme(&options.rom_path)).map(|path| {
+        theme::load(&path, theme::Theme::default()).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        })
+    });
+    let look = theme::resolve(options.palette, options.filter, theme);
+
+    if options.debug {
+        repl::run(&mut debugger::Debugger::new(chip));
+        return Ok(());
+    }
+
+    if let Some(frames) = options.frames {
+        run_frames(chip, frames, &options, look);
+    }
+
+    if options.headless {
+        run_headless(chip);
+        return Ok(());
+    }
+
+    let size = (
+        (VIDEO_WIDTH * options.scale + MARGIN * 2) as u32,
+        (VIDEO_HEIGHT * options.scale + MARGIN * 2) as u32,
+    );
+    *LAUNCH.lock().unwrap() = Some((options, chip, keymap, look));
 
     Display::run(WindowSettings {
         title: String::from("Chi-chan"),
-        size: (600, 320),
+        size,
         resizable: false,
         fullscreen: false,
         maximized: false,
@@ -35,69 +158,306 @@ fn main() -> Result<()>{
 
 }
 
+fn load_chip(options: &cli::Options) -> Chip8 {
+    let mut chip = Chip8::new();
+    chip.unknown_opcode_policy = options.unknown_opcodes;
+    chip.speed = options.speed;
+    chip.variant = options.variant;
+    chip.quirks = options.quirks;
+    if let Some(seed) = options.seed {
+        chip.seed(seed);
+    }
+
+    if let Err(err) = chip.load_rom(&options.rom_path) {
+        eprintln!("error: {}: {}", options.rom_path, err);
+        process::exit(1);
+    }
+
+    if let Some(path) = &options.state {
+        let loaded = fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| chip.load_state(&data).map_err(|err| err.to_string()));
+
+        if let Err(err) = loaded {
+            eprintln!("error: {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+
+    chip
+}
+
+/// Save slot file for a rom, next to the rom itself
+fn slot_path(rom_path: &str, slot: usize) -> String {
+    format!("{}.{}.state", rom_path, slot)
+}
+
+/// Run the emulator without a window, one frame every 60th of a second
+fn run_headless(mut chip: Chip8) {
+    let frame_time = Duration::from_secs(1) / chip8::TIMER_HZ;
+
+    loop {
+        match chip.run_frame() {
+            Ok(chip8::StepEvent::Exited) => return,
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("error: machine halted: {}", err);
+                process::exit(1);
+            }
+        }
+        thread::sleep(frame_time);
+    }
+}
+
+/// Run a fixed number of frames, print the report and exit with the run's status
+fn run_frames(mut chip: Chip8, frames: u32, options: &cli::Options, look: theme::Theme) -> ! {
+    // the PNG shows the screen through the filter, which needs to see every frame
+    let mut renderer = Renderer::new(look.filter, look.palette);
+    let (ran, outcome) = headless::run_frames_with(&mut chip, &mut renderer, frames);
+    println!("{}", headless::report(&chip, ran, outcome));
+
+    if let Some(path) = &options.dump {
+        let dump = if path.ends_with(".png") {
+            if ran == 0 {
+                renderer.render(&chip.video);
+            }
+            let (width, height) = renderer.size();
+            screenshot::png(renderer.rgba(), width, height, options.scale)
+        } else {
+            Ok(format!("{}\n", headless::screen_ascii(&chip)).into_bytes())
+        };
+        if let Err(err) = dump.and_then(|dump| fs::write(path, dump)) {
+            eprintln!("error: {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+
+    process::exit(outcome.exit_code());
+}
+
+/// The window's side of the machine: keys picked up by `interact`, the picture `draw` paints and the speaker
+struct WindowFrontend {
+    keys: [u8; 16],
+    scale: usize,
+    speaker: Option<sound::Speaker>,
+    // the screen as RGBA through the display filter
+    renderer: Renderer,
+    // the renderer's picture changed since `image` was uploaded
+    stale: bool,
+    image: Option<Image>,
+}
+
+impl WindowFrontend {
+    fn new(scale: usize, renderer: Renderer, speaker: Option<sound::Speaker>) -> Self {
+        WindowFrontend {
+            keys: [0; 16],
+            scale,
+            speaker,
+            renderer,
+            stale: false,
+            image: None,
+        }
+    }
+
+    /// Switch colors, the next `draw` repaints the whole picture in them
+    fn set_palette(&mut self, palette: Palette) {
+        self.renderer.set_palette(palette);
+    }
+
+    /// The screen as a texture, uploaded again only when it changed since the last call
+    fn image(&mut self, gpu: &mut Gpu) -> Option<&Image> {
+        if self.stale {
+            let (width, height) = self.renderer.size();
+            let rgba = image::RgbaImage::from_raw(width as u32, height as u32, self.renderer.rgba().to_vec())
+                .expect("the renderer holds width * height RGBA pixels");
+            // a failed upload keeps the previous picture
+            if let Ok(image) = Image::from_image(gpu, &image::DynamicImage::ImageRgba8(rgba)) {
+                self.image = Some(image);
+            }
+            self.stale = false;
+        }
+
+        self.image.as_ref()
+    }
+}
+
+impl Frontend for WindowFrontend {
+    fn read_keys(&mut self, keypad: &mut [u8; 16]) {
+        *keypad = self.keys;
+    }
+
+    fn draw(&mut self, chip: &Chip8) {
+        // without a filter only the dirty rows are painted, with one the picture moves on every frame
+        if self.renderer.render(&chip.video) {
+            self.stale = true;
+        }
+    }
+
+    fn set_sound(&mut self, chip: &Chip8, beeping: bool) {
+        if let Some(speaker) = &self.speaker {
+            speaker.set_pattern(chip.audio_pattern, chip.pitch);
+            speaker.set_beeping(beeping);
+        }
+    }
+}
 
 struct Display {
     chip: Chip8,
+    frontend: WindowFrontend,
+    keymap: keymap::KeyMap,
+    rom_path: String,
+    rewind: rewind::Rewind,
+    rewinding: bool,
+    paused: bool,
+    halted: bool,
+}
+
+impl Display {
+    fn save_slot(&self, slot: usize) {
+        let path = slot_path(&self.rom_path, slot);
+
+        match fs::write(&path, self.chip.save_state()) {
+            Ok(()) => eprintln!("saved slot {} to {}", slot, path),
+            Err(err) => eprintln!("error: {}: {}", path, err),
+        }
+    }
+
+    fn load_slot(&mut self, slot: usize) {
+        let path = slot_path(&self.rom_path, slot);
+
+        let loaded = fs::read(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| self.chip.load_state(&data).map_err(|err| err.to_string()));
+
+        match loaded {
+            Ok(()) => {
+                eprintln!("loaded slot {} from {}", slot, path);
+                // a state from before the halt can run again
+                self.halted = false;
+                // history from before the load doesn't lead up to this state
+                self.rewind.clear();
+                self.rewind.record(&self.chip);
+            }
+            Err(err) => eprintln!("error: {}: {}", path, err),
+        }
+    }
 }
 
 impl Game for Display {
-    
-    type Input = ();
+
+    type Input = KeyboardAndMouse;
     type LoadingScreen = ();
-    const TICKS_PER_SECOND: u16 = 30;
+    const TICKS_PER_SECOND: u16 = chip8::TIMER_HZ as u16;
 
     fn load(_window: &Window) -> Task<Display>{
-        let mut chip = Chip8::new();
-        let _bc_test = String::from("./src/BC_test.ch8");
-        let _op_test = String::from("./src/test_opcode.ch8");
-        let _tetris = String::from("./src/tetris.ch8");
-        let rom_path = _op_test;
+        let (options, chip, keymap, look) = LAUNCH.lock().unwrap().take().expect("chip is loaded before the window opens");
+
+        // no sound device or muted, carry on silently
+        let speaker = if options.volume > 0.0 {
+            sound::Speaker::open(options.tone, options.volume)
+        } else {
+            None
+        };
+
+        let paused = options.paused;
+        let rom_path = options.rom_path;
+
+        let renderer = Renderer::new(look.filter, look.palette);
+        let mut frontend = WindowFrontend::new(options.scale, renderer, speaker);
+        frontend.draw(&chip);
 
-        chip.load_rom(&rom_path);
+        // the first recorded frame is as far back as rewinding goes
+        let mut rewind = rewind::Rewind::with_seconds(options.rewind, rewind::DEFAULT_BUDGET);
+        rewind.record(&chip);
 
-        Task::succeed(|| 
+        Task::succeed(move ||
             Display {
-            chip: chip,
+            chip,
+            frontend,
+            keymap,
+            rom_path,
+            rewind,
+            rewinding: false,
+            paused,
+            halted: false,
         })
     }
 
-    fn update(&mut self, _window: &Window){
-        self.chip.cycle();
-    }
+    fn interact(&mut self, input: &mut KeyboardAndMouse, _window: &mut Window) {
+        let keyboard = input.keyboard();
 
-    fn draw(&mut self, frame: &mut Frame, _timer: &Timer){
-        frame.clear(Color::new(56.0/255.0, 168.0/255.0, 209.0/255.0, 1.0));
-
-        let mut mesh = Mesh::new_with_tolerance(0.0);
+        for (key, code) in self.keymap.keys.iter().enumerate() {
+            self.frontend.keys[key] = keyboard.is_key_pressed(*code) as u8;
+        }
 
-        let chip_buffer = self.chip.video_to_2d();
-        let pixel_scale = PIXEL_SIZE;
-        let black = Color::new(0.0, 0.0, 0.0, 1.0);
-        let white = Color::new(1.0, 1.0, 1.0, 1.0);
+        // with `--rewind 0` holding the key would keep reloading the same frame
+        self.rewinding = self.rewind.is_enabled() && keyboard.is_key_pressed(REWIND_KEY);
 
-        // iterate thru video buffer and add the pizel to mesh
-        for y in 0..VIDEO_HEIGHT {
-            for x in 0..VIDEO_WIDTH {
-                let pixel = chip_buffer[x][y];
+        if keyboard.was_key_released(PAUSE_KEY) {
+            self.paused = !self.paused;
+            eprintln!("{}", if self.paused { "paused" } else { "resumed" });
+        }
 
-                let rect = Shape::Rectangle(Rectangle {
-                    x: (x * pixel_scale) as f32,
-                    y: (y * pixel_scale) as f32,
-                    height: pixel_scale as f32,
-                    width: pixel_scale as f32,
-                });
+        if keyboard.was_key_released(PALETTE_KEY) {
+            let palette = self.frontend.renderer.palette().next();
+            self.frontend.set_palette(palette);
+            eprintln!("palette: {}", palette.name().unwrap_or("custom"));
+        }
 
-                if pixel > 0 {
-                    mesh.fill(rect, white);
+        let shift = keyboard.is_key_pressed(KeyCode::LShift) || keyboard.is_key_pressed(KeyCode::RShift);
+        for (slot, code) in SLOT_KEYS.iter().enumerate() {
+            if keyboard.was_key_released(*code) {
+                if shift {
+                    self.save_slot(slot + 1);
                 } else {
-                    mesh.fill(rect, black);
+                    self.load_slot(slot + 1);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, _window: &Window){
+        if self.rewinding {
+            // one frame back per frame held, a halted machine comes back to life
+            self.rewind.rewind(&mut self.chip, 1);
+            self.halted = false;
+        }
+
+        let running = !self.paused && !self.halted && !self.rewinding;
+
+        if running {
+            match frontend::run_frame(&mut self.chip, &mut self.frontend) {
+                Ok(chip8::StepEvent::Exited) => {
+                    eprintln!("rom exited");
+                    self.halted = true;
+                }
+                Ok(_) => self.rewind.record(&self.chip),
+                Err(err) => {
+                    eprintln!("error: machine halted: {}", err);
+                    self.halted = true;
                 }
             }
+        } else {
+            // paused, halted or rewinding: keep the picture current and the buzzer quiet
+            self.frontend.set_sound(&self.chip, false);
+            self.frontend.draw(&self.chip);
+            self.chip.video.clear_dirty();
         }
-        
-        let translate = Transformation::translate(Vector::new(10.0, 10.0));
-        let mut target = frame.as_target();
-        mesh.draw(&mut target.transform(translate));
+    }
+
+    fn draw(&mut self, frame: &mut Frame, _timer: &Timer){
+        let [r, g, b] = self.frontend.renderer.palette().background;
+        frame.clear(Color::from_rgb(r, g, b));
 
+        let scale = self.frontend.scale;
+        if let Some(image) = self.frontend.image(frame.gpu()) {
+            // one quad the size of the screen, the texture is sampled nearest neighbour
+            let quad = Quad {
+                position: Point::new(MARGIN as f32, MARGIN as f32),
+                size: ((VIDEO_WIDTH * scale) as f32, (VIDEO_HEIGHT * scale) as f32),
+                ..Default::default()
+            };
+            image.draw(quad, &mut frame.as_target());
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,239 @@
+// Terminal front end for the debugger, `chichan --debug rom.ch8`
+use chichan_core::debugger::{self, Debugger, Stop};
+use chichan_core::disasm;
+use chichan_core::headless;
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Write};
+
+pub const HELP: &str = "\
+Commands:
+  s, step [N]             Run N instructions [default: 1]
+  n, next                 Step over a 2nnn call
+  finish                  Run until the current subroutine returns
+  c, continue [N]         Run until something stops the machine, at most N instructions
+  b, break <ADDR> [if <Vx OP VALUE>]
+                          Break at ADDR, optionally only when the condition holds (==, !=, <, <=, >, >=)
+  d, delete <ADDR>        Remove the breakpoint at ADDR
+  w, watch <ADDR> [LEN]   Stop after Fx33/Fx55 writes to ADDR..ADDR+LEN [default LEN: 1]
+  unwatch <ADDR>          Remove the watchpoint starting at ADDR
+  l, list                 Show breakpoints and watchpoints
+  r, regs                 Show registers, timers and the stack
+  m, mem <ADDR> [LEN]     Dump LEN bytes of memory [default LEN: 16]
+  key <K> <down|up>       Press or release keypad key K (0-F)
+  screen                  Print the display
+  h, help                 Print this help
+  q, quit                 Leave the debugger
+Numbers are decimal, or hex with a 0x prefix.";
+
+/// Instructions `continue` runs when no limit is given, a minute of emulated time at the default speed
+pub const DEFAULT_CONTINUE_CYCLES: u64 = 700 * 60;
+
+/// What the REPL should do after a command
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    Output(String),
+    Quit,
+}
+
+/// Read commands from stdin until `quit` or end of input
+pub fn run(debugger: &mut Debugger) {
+    let stdin = io::stdin();
+    println!("{}", status(debugger));
+
+    loop {
+        print!("(chichan) ");
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+
+        match execute(debugger, &line) {
+            Ok(Reply::Output(output)) => {
+                if !output.is_empty() {
+                    println!("{}", output);
+                }
+            }
+            Ok(Reply::Quit) => return,
+            Err(err) => println!("error: {}", err),
+        }
+    }
+}
+
+/// Run one command line against the debugger
+pub fn execute(debugger: &mut Debugger, line: &str) -> Result<Reply, String> {
+    let mut words = line.split_whitespace();
+    let command = match words.next() {
+        Some(command) => command,
+        None => return Ok(Reply::Output(String::new())),
+    };
+    let args: Vec<&str> = words.collect();
+
+    let output = match command {
+        "s" | "step" => {
+            let count = optional_number(&args, 0, 1)?;
+            let mut stop = Stop::Done;
+            for _ in 0..count {
+                stop = debugger.step();
+                if stop != Stop::Done {
+                    break;
+                }
+            }
+            stopped(debugger, stop)
+        }
+        "n" | "next" => {
+            let stop = debugger.step_over(DEFAULT_CONTINUE_CYCLES);
+            stopped(debugger, stop)
+        }
+        "finish" => {
+            let stop = debugger.step_out(DEFAULT_CONTINUE_CYCLES)?;
+            stopped(debugger, stop)
+        }
+        "c" | "continue" => {
+            let cycles = optional_number(&args, 0, DEFAULT_CONTINUE_CYCLES as u32)?;
+            let stop = debugger.cont(cycles as u64);
+            stopped(debugger, stop)
+        }
+        "b" | "break" => {
+            let addr = address(&args, 0)?;
+            let condition = match args.get(1) {
+                Some(&"if") => Some(args[2..].join(" ").parse()?),
+                Some(word) => return Err(format!("expected 'if', got '{}'", word)),
+                None => None,
+            };
+            debugger.add_conditional_breakpoint(addr, condition);
+            format!("breakpoint at {:#05X}", addr)
+        }
+        "d" | "delete" => {
+            let addr = address(&args, 0)?;
+            if !debugger.remove_breakpoint(addr) {
+                return Err(format!("no breakpoint at {:#05X}", addr));
+            }
+            format!("removed breakpoint at {:#05X}", addr)
+        }
+        "w" | "watch" => {
+            let addr = address(&args, 0)?;
+            let len = optional_number(&args, 1, 1)?;
+            let len = u16::try_from(len).map_err(|_| format!("watch length {} is over 0xFFFF", len))?;
+            debugger.add_watchpoint(addr, len);
+            format!("watching {:#05X}..{:#05X}", addr, addr as u32 + len as u32)
+        }
+        "unwatch" => {
+            let addr = address(&args, 0)?;
+            if !debugger.remove_watchpoint(addr) {
+                return Err(format!("no watchpoint at {:#05X}", addr));
+            }
+            format!("removed watchpoint at {:#05X}", addr)
+        }
+        "l" | "list" => list(debugger),
+        "r" | "regs" => headless::registers(&debugger.chip),
+        "m" | "mem" => {
+            let addr = address(&args, 0)? as usize;
+            let len = optional_number(&args, 1, 16)? as usize;
+            memory(debugger, addr, len)?
+        }
+        "key" => {
+            let key = args
+                .first()
+                .and_then(|key| u8::from_str_radix(key, 16).ok())
+                .filter(|key| *key < 16)
+                .ok_or_else(|| String::from("expected a keypad key (0-F)"))?;
+            let pressed = match args.get(1) {
+                Some(&"down") => 1,
+                Some(&"up") => 0,
+                _ => return Err(String::from("expected 'down' or 'up'")),
+            };
+            debugger.chip.keypad[key as usize] = pressed;
+            String::new()
+        }
+        "screen" => headless::screen_ascii(&debugger.chip),
+        "h" | "help" => String::from(HELP),
+        "q" | "quit" => return Ok(Reply::Quit),
+        _ => return Err(format!("unknown command '{}', try 'help'", command)),
+    };
+
+    Ok(Reply::Output(output))
+}
+
+fn address(args: &[&str], position: usize) -> Result<u16, String> {
+    let value = args
+        .get(position)
+        .ok_or_else(|| String::from("expected an address"))?;
+    let value = debugger::parse_value(value)?;
+    if value > 0xFFFF {
+        return Err(format!("{:#X} is not an address", value));
+    }
+    Ok(value as u16)
+}
+
+fn optional_number(args: &[&str], position: usize, default: u32) -> Result<u32, String> {
+    match args.get(position) {
+        Some(value) => debugger::parse_value(value),
+        None => Ok(default),
+    }
+}
+
+/// Report a stop followed by where the machine is
+fn stopped(debugger: &Debugger, stop: Stop) -> String {
+    match stop {
+        Stop::Done => status(debugger),
+        stop => format!("{}\n{}", stop, status(debugger)),
+    }
+}
+
+/// The pc and the instruction about to run
+fn status(debugger: &Debugger) -> String {
+    let pc = debugger.chip.pc as usize;
+    if pc + 1 >= debugger.chip.mem_size() {
+        return format!("{:#05X}: <out of memory>", pc);
+    }
+
+    let opcode = ((debugger.chip.memory[pc] as u16) << 8) | debugger.chip.memory[pc + 1] as u16;
+    match disasm::decode(&debugger.chip.memory[pc..debugger.chip.mem_size()], debugger.chip.variant) {
+        Some(op) => format!("{:#05X}: {:04X}  {}", pc, opcode, op),
+        None => format!("{:#05X}: {:04X}  <unknown>", pc, opcode),
+    }
+}
+
+fn list(debugger: &Debugger) -> String {
+    let mut lines = Vec::new();
+    for breakpoint in debugger.breakpoints() {
+        match &breakpoint.condition {
+            Some(condition) => lines.push(format!("break {:#05X} if {}", breakpoint.addr, condition)),
+            None => lines.push(format!("break {:#05X}", breakpoint.addr)),
+        }
+    }
+    for watchpoint in debugger.watchpoints() {
+        lines.push(format!(
+            "watch {:#05X}..{:#05X}",
+            watchpoint.addr,
+            watchpoint.addr as u32 + watchpoint.len as u32
+        ));
+    }
+
+    if lines.is_empty() {
+        String::from("no breakpoints or watchpoints")
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn memory(debugger: &Debugger, addr: usize, len: usize) -> Result<String, String> {
+    let end = addr + len;
+    if end > debugger.chip.mem_size() {
+        return Err(format!("{:#05X}..{:#05X} is past the end of memory", addr, end));
+    }
+
+    let lines: Vec<String> = debugger.chip.memory[addr..end]
+        .chunks(16)
+        .enumerate()
+        .map(|(row, bytes)| {
+            let bytes: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            format!("{:#05X}: {}", addr + row * 16, bytes.join(" "))
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,23 @@
+// PNG screenshots for headless runs
+use image::png::PNGEncoder;
+use image::ColorType;
+use std::io;
+
+/// A `width` x `height` RGBA picture, e.g. a `filter::Renderer`'s, as an RGB PNG
+/// with each pixel `scale` pixels wide. The alpha channel is dropped
+pub fn png(rgba: &[u8], width: usize, height: usize, scale: usize) -> io::Result<Vec<u8>> {
+    assert_eq!(rgba.len(), width * height * 4, "RGBA picture has the wrong size");
+    let scale = scale.max(1);
+
+    let mut rgb = Vec::with_capacity(width * height * scale * scale * 3);
+    for y in 0..height * scale {
+        for x in 0..width * scale {
+            let pixel = ((y / scale) * width + x / scale) * 4;
+            rgb.extend_from_slice(&rgba[pixel..pixel + 3]);
+        }
+    }
+
+    let mut png = Vec::new();
+    PNGEncoder::new(&mut png).encode(&rgb, (width * scale) as u32, (height * scale) as u32, ColorType::RGB(8))?;
+    Ok(png)
+}
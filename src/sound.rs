@@ -0,0 +1,92 @@
+// Plays the buzzer through the default audio device
+use chichan_core::audio::{Buzzer, DEFAULT_PITCH, DEFAULT_SAMPLE_RATE};
+use rodio::{Sink, Source};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// XO-CHIP audio pattern and pitch handed from the emulator to the audio thread
+type SharedPattern = Arc<Mutex<(Option<[u8; 16]>, u8)>>;
+
+/// Endless rodio source pulling samples from a `Buzzer`
+struct BuzzerSource {
+    buzzer: Buzzer,
+    beeping: Arc<AtomicBool>,
+    pattern: SharedPattern,
+}
+
+impl Iterator for BuzzerSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // don't stall the audio thread, the pattern is picked up on a later sample
+        if let Ok(pattern) = self.pattern.try_lock() {
+            self.buzzer.pattern = pattern.0;
+            self.buzzer.pitch = pattern.1;
+        }
+
+        Some(self.buzzer.next_sample(self.beeping.load(Ordering::Relaxed)))
+    }
+}
+
+impl Source for BuzzerSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.buzzer.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Handle to the playing buzzer, the tone stops when it's dropped
+pub struct Speaker {
+    beeping: Arc<AtomicBool>,
+    pattern: SharedPattern,
+    _sink: Sink,
+}
+
+impl Speaker {
+    /// Start the buzzer on the default output device, `None` if there's no device
+    pub fn open(frequency: f32, volume: f32) -> Option<Speaker> {
+        let device = rodio::default_output_device()?;
+        let beeping = Arc::new(AtomicBool::new(false));
+        let pattern = Arc::new(Mutex::new((None, DEFAULT_PITCH)));
+
+        let mut buzzer = Buzzer::new(DEFAULT_SAMPLE_RATE);
+        buzzer.frequency = frequency;
+        buzzer.volume = volume;
+
+        let sink = Sink::new(&device);
+        sink.append(BuzzerSource {
+            buzzer,
+            beeping: beeping.clone(),
+            pattern: pattern.clone(),
+        });
+
+        Some(Speaker {
+            beeping,
+            pattern,
+            _sink: sink,
+        })
+    }
+
+    pub fn set_beeping(&self, beeping: bool) {
+        self.beeping.store(beeping, Ordering::Relaxed);
+    }
+
+    /// Play an XO-CHIP audio pattern at `pitch` instead of the square wave, `None` goes back to the square wave
+    pub fn set_pattern(&self, pattern: Option<[u8; 16]>, pitch: u8) {
+        if let Ok(mut shared) = self.pattern.lock() {
+            *shared = (pattern, pitch);
+        }
+    }
+}
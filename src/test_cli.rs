@@ -0,0 +1,194 @@
+// Tests
+#[cfg(test)]
+mod test_cli {
+    use chichan_core::audio;
+    use chichan_core::chip8::{UnknownOpcodePolicy, Variant};
+    use super::super::cli::{self, Command, Options};
+    use chichan_core::filter::Filter;
+    use chichan_core::palette::Palette;
+    use chichan_core::quirks::Quirks;
+    use chichan_core::rewind;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let command = cli::parse(args(&["rom.ch8"])).unwrap();
+
+        assert_eq!(
+            command,
+            Command::Run(Options {
+                rom_path: String::from("rom.ch8"),
+                speed: cli::DEFAULT_SPEED,
+                scale: cli::DEFAULT_SCALE,
+                variant: Variant::Chip8,
+                quirks: Quirks::default(),
+                seed: None,
+                unknown_opcodes: UnknownOpcodePolicy::Halt,
+                keymap: None,
+                palette: None,
+                filter: None,
+                theme: None,
+                state: None,
+                rewind: rewind::DEFAULT_SECONDS,
+                tone: audio::DEFAULT_FREQUENCY,
+                volume: audio::DEFAULT_VOLUME,
+                paused: false,
+                headless: false,
+                frames: None,
+                dump: None,
+                debug: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_all_options() {
+        let command = cli::parse(args(&[
+            "--speed", "1000", "--scale", "4", "--quirks", "vip", "--seed", "42", "--unknown-opcodes", "log", "--keymap", "azerty.keys", "--palette", "lcd", "--filter", "phosphor:0.5:3", "--theme", "dark.theme", "--state", "rom.ch8.1.state", "--rewind", "0", "--tone", "880", "--volume", "0.5",
+            "--headless", "--frames", "600", "--dump", "screen.png", "--debug", "rom.ch8",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            command,
+            Command::Run(Options {
+                rom_path: String::from("rom.ch8"),
+                speed: 1000,
+                scale: 4,
+                variant: Variant::Chip8,
+                quirks: Quirks::COSMAC_VIP,
+                seed: Some(42),
+                unknown_opcodes: UnknownOpcodePolicy::Log,
+                keymap: Some(String::from("azerty.keys")),
+                palette: Some(Palette::LCD),
+                filter: Some(Filter::Phosphor {
+                    decay: 0.5,
+                    frames: 3,
+                }),
+                theme: Some(String::from("dark.theme")),
+                state: Some(String::from("rom.ch8.1.state")),
+                rewind: 0,
+                tone: 880.0,
+                volume: 0.5,
+                paused: false,
+                headless: true,
+                frames: Some(600),
+                dump: Some(String::from("screen.png")),
+                debug: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_paused() {
+        let options = match cli::parse(args(&["--paused", "rom.ch8"])).unwrap() {
+            Command::Run(options) => options,
+            other => panic!("expected options, got {:?}", other),
+        };
+        assert!(options.paused);
+    }
+
+    #[test]
+    fn test_parse_variant() {
+        let options = match cli::parse(args(&["--variant", "xochip", "rom.ch8"])).unwrap() {
+            Command::Run(options) => options,
+            other => panic!("expected options, got {:?}", other),
+        };
+        assert_eq!(options.variant, Variant::XoChip);
+        assert_eq!(options.quirks, Quirks::XO_CHIP);
+
+        // an explicit profile wins over the variant's
+        let options = match cli::parse(args(&["--quirks", "vip", "--variant", "schip", "rom.ch8"])).unwrap() {
+            Command::Run(options) => options,
+            other => panic!("expected options, got {:?}", other),
+        };
+        assert_eq!(options.variant, Variant::SuperChip);
+        assert_eq!(options.quirks, Quirks::COSMAC_VIP);
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            cli::parse(args(&["run", "--headless", "--frames", "600", "rom.ch8"])).unwrap(),
+            cli::parse(args(&["--headless", "--frames", "600", "rom.ch8"])).unwrap()
+        );
+        assert!(cli::parse(args(&["run", "--frames", "600", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["run", "--headless", "--dump", "out.png", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["run", "--headless", "--frames", "-1", "rom.ch8"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_disasm() {
+        assert_eq!(
+            cli::parse(args(&["disasm", "rom.ch8"])).unwrap(),
+            Command::Disasm {
+                rom_path: String::from("rom.ch8"),
+                variant: Variant::Chip8,
+            }
+        );
+        assert_eq!(
+            cli::parse(args(&["disasm", "--variant", "xochip", "rom.ch8"])).unwrap(),
+            Command::Disasm {
+                rom_path: String::from("rom.ch8"),
+                variant: Variant::XoChip,
+            }
+        );
+        assert!(cli::parse(args(&["disasm"])).is_err());
+        assert!(cli::parse(args(&["disasm", "--speed", "10", "rom.ch8"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_asm() {
+        assert_eq!(
+            cli::parse(args(&["asm", "game.asm", "-o", "out.ch8"])).unwrap(),
+            Command::Asm {
+                source_path: String::from("game.asm"),
+                output_path: String::from("out.ch8"),
+            }
+        );
+        assert_eq!(
+            cli::parse(args(&["asm", "game.asm"])).unwrap(),
+            Command::Asm {
+                source_path: String::from("game.asm"),
+                output_path: String::from("game.ch8"),
+            }
+        );
+        assert!(cli::parse(args(&["asm"])).is_err());
+        assert!(cli::parse(args(&["asm", "game.asm", "-o"])).is_err());
+        assert!(cli::parse(args(&["asm", "a.asm", "b.asm"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert_eq!(cli::parse(args(&["rom.ch8", "--help"])).unwrap(), Command::Help);
+        assert_eq!(cli::parse(args(&["-h"])).unwrap(), Command::Help);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(cli::parse(args(&[])).is_err());
+        assert!(cli::parse(args(&["--speed"])).is_err());
+        assert!(cli::parse(args(&["--speed", "fast", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--scale", "0", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--scale", "33", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--scale", "18446744073709551615", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--paused", "--headless", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--fullscreen", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["a.ch8", "b.ch8"])).is_err());
+        assert!(cli::parse(args(&["--unknown-opcodes", "panic", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--volume", "2", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--quirks", "eti660", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--tone", "0", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--variant", "megachip", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["rom.ch8", "--state"])).is_err());
+        assert!(cli::parse(args(&["--rewind", "-1", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--seed", "abc", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--seed", "0", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--palette", "sepia", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--filter", "blur", "rom.ch8"])).is_err());
+        assert!(cli::parse(args(&["--filter", "phosphor:2", "rom.ch8"])).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+// Tests
+#[cfg(test)]
+mod test_keymap {
+    use super::super::keymap::KeyMap;
+    use coffee::input::keyboard::KeyCode;
+
+    #[test]
+    fn test_default_layout() {
+        let map = KeyMap::default();
+
+        assert_eq!(map.keys[0x1], KeyCode::Key1);
+        assert_eq!(map.keys[0xC], KeyCode::Key4);
+        assert_eq!(map.keys[0x0], KeyCode::X);
+        assert_eq!(map.keys[0xF], KeyCode::V);
+    }
+
+    #[test]
+    fn test_parse_rebinds() {
+        let map = KeyMap::parse(
+            "# azerty\n\
+             4 = A   # top row\n\
+             5 = Z\n\
+             \n\
+             a = W\n\
+             0 = Numpad0\n",
+        )
+        .unwrap();
+
+        assert_eq!(map.keys[0x4], KeyCode::A);
+        assert_eq!(map.keys[0x5], KeyCode::Z);
+        assert_eq!(map.keys[0xA], KeyCode::W);
+        assert_eq!(map.keys[0x0], KeyCode::Numpad0);
+        // untouched keys keep the default
+        assert_eq!(map.keys[0x1], KeyCode::Key1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            KeyMap::parse("1 = 1\n10 = A").unwrap_err(),
+            "line 2: '10' is not a keypad key (0-F)"
+        );
+        assert_eq!(
+            KeyMap::parse("1 = Hyper").unwrap_err(),
+            "line 1: unknown keyboard key 'Hyper'"
+        );
+        assert!(KeyMap::parse("1 A").is_err());
+    }
+}
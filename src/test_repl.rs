@@ -0,0 +1,84 @@
+// Tests
+#[cfg(test)]
+mod test_repl {
+    use super::super::repl::{self, Reply};
+    use chichan_core::asm;
+    use chichan_core::chip8::{Chip8, Variant};
+    use chichan_core::debugger::Debugger;
+
+    const SOURCE: &str = "
+        loop:
+            ADD V0, 1
+            CALL store
+            SE V0, 5
+            JMP loop
+        end:
+            JMP end
+        store:
+            LD I, 0x300
+            LD B, V0
+            RET
+    ";
+
+    fn debugger() -> Debugger {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&asm::assemble(SOURCE).unwrap()).unwrap();
+        Debugger::new(chip)
+    }
+
+    #[test]
+    fn test_repl_commands() {
+        let mut debugger = debugger();
+
+        assert!(repl::execute(&mut debugger, "break 0x20C if V0 == 2").is_ok());
+        assert!(repl::execute(&mut debugger, "watch 0x300 3").is_ok());
+        match repl::execute(&mut debugger, "list").unwrap() {
+            Reply::Output(output) => {
+                assert_eq!(output, "break 0x20C if V0 == 0x02\nwatch 0x300..0x303");
+            }
+            Reply::Quit => panic!("list quit"),
+        }
+
+        repl::execute(&mut debugger, "unwatch 0x300").unwrap();
+        match repl::execute(&mut debugger, "continue").unwrap() {
+            Reply::Output(output) => assert_eq!(output, "breakpoint at 0x20C\n0x20C: F033  LD B, V0"),
+            Reply::Quit => panic!("continue quit"),
+        }
+
+        repl::execute(&mut debugger, "step 2").unwrap();
+        assert_eq!(debugger.chip.pc, 0x204);
+
+        repl::execute(&mut debugger, "key a down").unwrap();
+        assert_eq!(debugger.chip.keypad[0xA], 1);
+
+        match repl::execute(&mut debugger, "mem 0x300 3").unwrap() {
+            Reply::Output(output) => assert_eq!(output, "0x300: 00 00 02"),
+            Reply::Quit => panic!("mem quit"),
+        }
+
+        assert_eq!(repl::execute(&mut debugger, "quit"), Ok(Reply::Quit));
+        assert!(repl::execute(&mut debugger, "jump").is_err());
+        assert!(repl::execute(&mut debugger, "break").is_err());
+        assert!(repl::execute(&mut debugger, "delete 0x222").is_err());
+        assert!(repl::execute(&mut debugger, "mem 0xFFF 2").is_err());
+        assert!(repl::execute(&mut debugger, "watch 0x300 0x10000").is_err());
+        assert!(repl::execute(&mut debugger, "finish").is_err());
+    }
+
+    #[test]
+    fn test_status_follows_the_variant() {
+        let mut chip = Chip8::new();
+        chip.load_rom_bytes(&[0xF0, 0x00, 0x12, 0x34]).unwrap();
+        let mut debugger = Debugger::new(chip);
+
+        // stepping 0 instructions just shows where the machine is
+        let status = |debugger: &mut Debugger| match repl::execute(debugger, "step 0").unwrap() {
+            Reply::Output(output) => output,
+            Reply::Quit => panic!("step quit"),
+        };
+        assert_eq!(status(&mut debugger), "0x200: F000  <unknown>");
+
+        debugger.chip.variant = Variant::XoChip;
+        assert_eq!(status(&mut debugger), "0x200: F000  LD I, long 0x1234");
+    }
+}
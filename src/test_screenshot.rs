@@ -0,0 +1,51 @@
+// Tests
+#[cfg(test)]
+mod test_screenshot {
+    use super::super::screenshot;
+    use chichan_core::filter::{Filter, Renderer};
+    use chichan_core::{Chip8, Palette};
+    use image::RgbImage;
+
+    fn decode(png: &[u8]) -> RgbImage {
+        image::load_from_memory(png).unwrap().to_rgb()
+    }
+
+    #[test]
+    fn test_png() {
+        let chip = Chip8::new();
+        let png = screenshot::png(&chip.video.to_rgba(&Palette::default()), chip.width(), chip.height(), 2).unwrap();
+
+        assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']);
+        let image = decode(&png);
+        assert_eq!(image.dimensions(), (128, 64));
+        assert!(image.pixels().all(|pixel| pixel.data == [0, 0, 0]));
+    }
+
+    #[test]
+    fn test_png_palette_and_scale() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 0, 0, true);
+        let png = screenshot::png(&chip.video.to_rgba(&Palette::AMBER), chip.width(), chip.height(), 3).unwrap();
+
+        let image = decode(&png);
+        assert_eq!(image.dimensions(), (192, 96));
+        assert_eq!(image.get_pixel(2, 2).data, [255, 176, 0]);
+        assert_eq!(image.get_pixel(3, 0).data, [26, 13, 0]);
+        assert_eq!(image.get_pixel(0, 3).data, [26, 13, 0]);
+    }
+
+    #[test]
+    fn test_png_of_a_renderer() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 5, 1, true);
+        let mut renderer = Renderer::new(Filter::Off, Palette::CLASSIC);
+        renderer.render(&chip.video);
+        let (width, height) = renderer.size();
+
+        let png = screenshot::png(renderer.rgba(), width, height, 1).unwrap();
+
+        let image = decode(&png);
+        assert_eq!(image.get_pixel(5, 1).data, [255, 255, 255]);
+        assert_eq!(image.get_pixel(4, 1).data, [0, 0, 0]);
+    }
+}
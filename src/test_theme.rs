@@ -0,0 +1,113 @@
+// Tests
+#[cfg(test)]
+mod test_theme {
+    use super::super::theme::{self, Theme};
+    use chichan_core::filter::{self, Filter};
+    use chichan_core::palette::Palette;
+
+    fn on(palette: Palette) -> Theme {
+        Theme {
+            palette,
+            filter: Filter::Off,
+        }
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let source = "\
+# comment
+palette = amber
+
+background = #102030
+both = #FFffFF
+";
+        let palette = theme::parse(source, on(Palette::CLASSIC)).unwrap().palette;
+
+        assert_eq!(palette.background, [0x10, 0x20, 0x30]);
+        assert_eq!(palette.foreground, Palette::AMBER.foreground);
+        assert_eq!(palette.plane2, Palette::AMBER.plane2);
+        assert_eq!(palette.both, [0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_parse_keeps_base() {
+        let base = Theme {
+            palette: Palette::LCD,
+            filter: Filter::Merge,
+        };
+        let theme = theme::parse("foreground = #00FF00", base).unwrap();
+
+        assert_eq!(theme.palette.foreground, [0, 0xFF, 0]);
+        assert_eq!(theme.palette.background, Palette::LCD.background);
+        assert_eq!(theme.filter, Filter::Merge);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let theme = theme::parse("filter = phosphor:0.8\npalette = amber", on(Palette::CLASSIC)).unwrap();
+
+        assert_eq!(
+            theme.filter,
+            Filter::Phosphor {
+                decay: 0.8,
+                frames: filter::DEFAULT_FRAMES,
+            }
+        );
+        assert_eq!(theme.palette, Palette::AMBER);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |source: &str| theme::parse(source, on(Palette::CLASSIC)).unwrap_err();
+
+        assert_eq!(parse("background"), "line 1: expected '<name> = <value>'");
+        assert_eq!(parse("\nborder = #000000"), "line 2: unknown setting 'border'");
+        assert_eq!(parse("background = 000000"), "line 1: '000000' is not a #RRGGBB color");
+        assert_eq!(parse("background = #0000"), "line 1: '#0000' is not a #RRGGBB color");
+        assert!(parse("palette = sepia").starts_with("line 1: unknown palette 'sepia'"));
+        assert!(parse("filter = blur").starts_with("line 1: unknown filter 'blur'"));
+    }
+
+    #[test]
+    fn test_resolve_priority() {
+        let theme = Theme {
+            palette: Palette::AMBER,
+            filter: Filter::Merge,
+        };
+
+        // flag, then theme, then default
+        assert_eq!(theme::resolve(None, None, None), Theme::default());
+        assert_eq!(theme::resolve(None, None, Some(theme)), theme);
+        assert_eq!(
+            theme::resolve(Some(Palette::LCD), None, Some(theme)),
+            Theme {
+                palette: Palette::LCD,
+                filter: Filter::Merge,
+            }
+        );
+        assert_eq!(
+            theme::resolve(None, Some(Filter::Off), Some(theme)),
+            Theme {
+                palette: Palette::AMBER,
+                filter: Filter::Off,
+            }
+        );
+        assert_eq!(theme::resolve(Some(Palette::LCD), Some(Filter::Merge), None).palette, Palette::LCD);
+    }
+
+    #[test]
+    fn test_shipped_theme() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/themes/pocket.theme");
+        let theme = theme::load(path, on(Palette::CLASSIC)).unwrap();
+
+        assert_eq!(theme.palette.background, [0xC4, 0xCF, 0xA1]);
+    }
+
+    #[test]
+    fn test_rom_theme() {
+        let roms = concat!(env!("CARGO_MANIFEST_DIR"), "/themes/pocket");
+
+        assert_eq!(theme::rom_theme(roms), Some(format!("{}.theme", roms)));
+        assert_eq!(theme::rom_theme(concat!(env!("CARGO_MANIFEST_DIR"), "/no-such-rom.ch8")), None);
+    }
+}
@@ -0,0 +1,101 @@
+// Tests
+#[cfg(test)]
+mod test_window {
+    use super::super::WindowFrontend;
+    use chichan_core::filter::{self, Filter, Renderer};
+    use chichan_core::{Chip8, Frontend, Palette};
+
+    const WHITE: [u8; 4] = [255, 255, 255, 0xFF];
+
+    fn window(filter: Filter) -> WindowFrontend {
+        WindowFrontend::new(8, Renderer::new(filter, Palette::CLASSIC), None)
+    }
+
+    #[test]
+    fn test_first_draw_fills_the_picture() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 2, 1, true);
+        let mut frontend = window(Filter::Off);
+
+        frontend.draw(&chip);
+
+        assert_eq!(frontend.renderer.size(), (64, 32));
+        assert_eq!(frontend.renderer.rgba().len(), 64 * 32 * 4);
+        assert_eq!(&frontend.renderer.rgba()[(64 + 2) * 4..(64 + 3) * 4], &WHITE);
+        assert!(frontend.stale);
+    }
+
+    #[test]
+    fn test_only_dirty_rows_are_rewritten() {
+        let mut chip = Chip8::new();
+        let mut frontend = window(Filter::Off);
+        frontend.draw(&chip);
+        chip.video.clear_dirty();
+        frontend.stale = false;
+
+        // nothing changed, nothing to upload
+        frontend.draw(&chip);
+        assert!(!frontend.stale);
+
+        chip.video.set(0, 0, 5, true);
+        frontend.draw(&chip);
+
+        assert!(frontend.stale);
+        assert_eq!(&frontend.renderer.rgba()[5 * 64 * 4..5 * 64 * 4 + 4], &WHITE);
+    }
+
+    #[test]
+    fn test_palette_repaints_everything() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 0, 0, true);
+        let mut frontend = window(Filter::Off);
+        frontend.draw(&chip);
+        chip.video.clear_dirty();
+        frontend.stale = false;
+
+        // only the next frame paints, the filter doesn't move on in between
+        frontend.set_palette(Palette::AMBER);
+        assert!(!frontend.stale);
+        assert_eq!(&frontend.renderer.rgba()[..4], &WHITE);
+
+        frontend.draw(&chip);
+        assert!(frontend.stale);
+        assert_eq!(&frontend.renderer.rgba()[..4], &[255, 176, 0, 0xFF]);
+        assert_eq!(&frontend.renderer.rgba()[4..8], &[26, 13, 0, 0xFF]);
+    }
+
+    #[test]
+    fn test_mode_switch_resizes_the_picture() {
+        let mut chip = Chip8::new();
+        let mut frontend = window(Filter::Off);
+        frontend.draw(&chip);
+        chip.video.clear_dirty();
+
+        chip.OP_00FF().unwrap();
+        frontend.draw(&chip);
+
+        assert_eq!(frontend.renderer.size(), (128, 64));
+        assert_eq!(frontend.renderer.rgba().len(), 128 * 64 * 4);
+    }
+
+    #[test]
+    fn test_filter_keeps_uploading_while_fading() {
+        let mut chip = Chip8::new();
+        chip.video.set(0, 0, 0, true);
+        let mut frontend = window(Filter::Phosphor {
+            decay: 0.5,
+            frames: filter::DEFAULT_FRAMES,
+        });
+        frontend.draw(&chip);
+        chip.video.clear_dirty();
+
+        chip.video.set(0, 0, 0, false);
+        chip.video.clear_dirty();
+        frontend.stale = false;
+
+        // the core reports nothing new but the pixel is still fading out
+        frontend.draw(&chip);
+        assert!(frontend.stale);
+        assert_eq!(&frontend.renderer.rgba()[..4], &[127, 127, 127, 0xFF]);
+    }
+}
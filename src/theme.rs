@@ -0,0 +1,105 @@
+// Theme files: a palette preset with some of its colors replaced, and a display filter
+use chichan_core::filter::Filter;
+use chichan_core::palette::{Palette, Rgb};
+use std::fs;
+use std::path::Path;
+
+/// How the display looks, what a theme file sets
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Theme {
+    pub palette: Palette,
+    pub filter: Filter,
+}
+
+/// Read a theme file on top of `base`, see `parse` for the format
+pub fn load(path: &str, base: Theme) -> Result<Theme, String> {
+    let source = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    parse(&source, base).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Parse a theme from text. One setting per line, `<name> = <value>`:
+///
+/// ```text
+/// # a blue LCD
+/// palette = lcd
+/// background = #C4CFA1
+/// foreground = #1F1F5F
+/// filter = phosphor:0.5
+/// ```
+///
+/// `palette` picks the preset to start from, otherwise it's `base`'s. `background`, `foreground`,
+/// `plane2` and `both` replace one of its colors, written as `#RRGGBB`.
+/// `filter` takes the same values as `--filter`. Later lines win
+pub fn parse(source: &str, base: Theme) -> Result<Theme, String> {
+    let mut theme = base;
+
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        // colors start with `#` too, so only whole lines are comments
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: String| format!("line {}: {}", number + 1, message);
+
+        let mut parts = line.splitn(2, '=');
+        let name = parts.next().unwrap_or("").trim();
+        let value = parts
+            .next()
+            .ok_or_else(|| error(String::from("expected '<name> = <value>'")))?
+            .trim();
+
+        match name {
+            "palette" => {
+                theme.palette = value.parse().map_err(error)?;
+                continue;
+            }
+            "filter" => {
+                theme.filter = value.parse().map_err(error)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        let color = match name {
+            "background" => &mut theme.palette.background,
+            "foreground" => &mut theme.palette.foreground,
+            "plane2" => &mut theme.palette.plane2,
+            "both" => &mut theme.palette.both,
+            _ => return Err(error(format!("unknown setting '{}'", name))),
+        };
+        *color = parse_color(value).ok_or_else(|| error(format!("'{}' is not a #RRGGBB color", value)))?;
+    }
+
+    Ok(theme)
+}
+
+/// What the display ends up with: `palette` and `filter` when the command line gives them,
+/// then the theme file's settings, then the defaults
+pub fn resolve(palette: Option<Palette>, filter: Option<Filter>, theme: Option<Theme>) -> Theme {
+    let theme = theme.unwrap_or_default();
+    Theme {
+        palette: palette.unwrap_or(theme.palette),
+        filter: filter.unwrap_or(theme.filter),
+    }
+}
+
+/// The theme a rom picks up without `--theme`: `<rom>.theme` next to it, if there is one
+pub fn rom_theme(rom_path: &str) -> Option<String> {
+    let path = format!("{}.theme", rom_path);
+    if Path::new(&path).is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+fn parse_color(value: &str) -> Option<Rgb> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok();
+    Some([channel(0)?, channel(1)?, channel(2)?])
+}